@@ -0,0 +1,157 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::titlecase::push_titlecase;
+
+
+/// Target style of a case conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case
+{
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `CONSTANT_CASE`
+    Constant,
+    /// `Sentence case`
+    Sentence,
+    /// `Title Case`
+    Title,
+}
+
+
+const NAMES: &[(&str, Case)] = &[
+    ("snake", Case::Snake),
+    ("kebab", Case::Kebab),
+    ("camel", Case::Camel),
+    ("pascal", Case::Pascal),
+    ("constant", Case::Constant),
+    ("sentence", Case::Sentence),
+    ("title", Case::Title),
+];
+
+
+impl FromStr for Case
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        NAMES.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, case)| *case)
+            .ok_or_else(|| format!("unknown case {}, expected one of: {}",
+                s, NAMES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+
+impl fmt::Display for Case
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let name = NAMES.iter().find(|(_, case)| case == self).map(|(name, _)| *name).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+
+/// Split `s` into words.
+///
+/// Any character that is not a letter or a digit separates words. Inside a
+/// run of letters and digits a new word starts at an uppercase letter that
+/// follows a lowercase letter or a digit (`camelCase`), or that ends an
+/// acronym and is followed by a lowercase letter (`HTTPResponse`).
+/// Digits stay with the letters before them (`utf8`, `HTTP2`).
+pub fn split_words(s: &str) -> Vec<&str>
+{
+    let mut words = Vec::new();
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut start: Option<usize> = None;
+
+    for (i, (pos, ch)) in chars.iter().enumerate()
+    {
+        if !ch.is_alphanumeric()
+        {
+            if let Some(st) = start.take()
+            {
+                words.push(&s[st..*pos]);
+            }
+            continue;
+        }
+
+        if let Some(st) = start
+        {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|(_, c)| *c);
+            let hump = ch.is_uppercase() && (prev.is_lowercase() || prev.is_numeric());
+            let acronym_end = ch.is_uppercase() && prev.is_uppercase()
+                && next.is_some_and(char::is_lowercase);
+
+            if hump || acronym_end
+            {
+                words.push(&s[st..*pos]);
+                start = Some(*pos);
+            }
+        }
+        else
+        {
+            start = Some(*pos);
+        }
+    }
+    if let Some(st) = start
+    {
+        words.push(&s[st..]);
+    }
+    words
+}
+
+
+/// Convert `s` to `case`, splitting it with [`split_words`].
+pub fn convert(s: &str, case: Case) -> String
+{
+    let words = split_words(s);
+    let mut out = String::with_capacity(s.len());
+
+    for (i, word) in words.iter().enumerate()
+    {
+        if i > 0
+        {
+            match case
+            {
+                Case::Snake | Case::Constant => out.push('_'),
+                Case::Kebab => out.push('-'),
+                Case::Sentence | Case::Title => out.push(' '),
+                Case::Camel | Case::Pascal => {}
+            }
+        }
+
+        match case
+        {
+            Case::Snake | Case::Kebab => out.push_str(&word.to_lowercase()),
+            Case::Constant => out.push_str(&word.to_uppercase()),
+            Case::Camel if i == 0 => out.push_str(&word.to_lowercase()),
+            Case::Sentence if i == 0 => push_capitalized(&mut out, word),
+            Case::Sentence => out.push_str(&word.to_lowercase()),
+            Case::Camel | Case::Pascal | Case::Title => push_capitalized(&mut out, word),
+        }
+    }
+    out
+}
+
+
+/// Titlecase the first character of `word` and lowercase the rest.
+fn push_capitalized(out: &mut String, word: &str)
+{
+    let mut chars = word.chars();
+    if let Some(first) = chars.next()
+    {
+        push_titlecase(out, first);
+        out.push_str(&chars.as_str().to_lowercase());
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests;
+
+pub mod case;
+pub mod markup;
+pub mod stream;
+pub mod style;
+pub mod titlecase;
+
+pub use style::TitleCaseStyle;
+use titlecase::push_titlecase;
+
+
+/// Upper-cases the first letter of every word, leaving the rest untouched.
+///
+/// A word starts after any whitespace character or one of the configured
+/// punctuation `boundaries`. The apostrophe is not a boundary by default,
+/// so that "don't" is not turned into "Don'T".
+///
+/// Words with an uppercase letter past the first one, such as acronyms
+/// ("NASA") and brands ("iPhone"), are left as they are. The [`TitleCaseStyle`]
+/// decides which of the other words are lowercased instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capitalizer
+{
+    boundaries: Vec<char>,
+    style: TitleCaseStyle,
+}
+
+
+impl Default for Capitalizer
+{
+    fn default() -> Self
+    {
+        Self { boundaries: vec!['-', '/'], style: TitleCaseStyle::Simple }
+    }
+}
+
+
+impl Capitalizer
+{
+    pub fn new(boundaries: &[char]) -> Self
+    {
+        Self { boundaries: boundaries.to_vec(), style: TitleCaseStyle::Simple }
+    }
+
+
+    pub fn with_style(mut self, style: TitleCaseStyle) -> Self
+    {
+        self.style = style;
+        self
+    }
+
+
+    pub fn is_boundary(&self, ch: char) -> bool
+    {
+        ch.is_whitespace() || self.boundaries.contains(&ch)
+    }
+
+
+    pub fn capitalize(&self, s: &str) -> String
+    {
+        let words = self.split_words(s);
+        let last = words.iter()
+            .rposition(|(start, end)| s[*start..*end].chars().any(char::is_alphanumeric));
+
+        let mut cap = String::with_capacity(s.len());
+        let mut prev_end = 0;
+        let mut first = true;
+
+        for (i, (start, end)) in words.iter().enumerate()
+        {
+            let word = &s[*start..*end];
+            cap.push_str(&s[prev_end..*start]);
+            self.push_word(&mut cap, word, first || Some(i) == last);
+
+            // A subtitle after a colon starts like a new title
+            first = word.ends_with([':', '?', '!']);
+            prev_end = *end;
+        }
+        cap.push_str(&s[prev_end..]);
+        cap
+    }
+
+
+    /// Byte ranges of the words of `s`.
+    fn split_words(&self, s: &str) -> Vec<(usize, usize)>
+    {
+        let mut words = Vec::new();
+        let mut start: Option<usize> = None;
+
+        for (i, ch) in s.char_indices()
+        {
+            match (self.is_boundary(ch), start)
+            {
+                (true, Some(st)) =>
+                {
+                    words.push((st, i));
+                    start = None;
+                }
+                (false, None) => start = Some(i),
+                _ => {}
+            }
+        }
+        if let Some(st) = start
+        {
+            words.push((st, s.len()));
+        }
+        words
+    }
+
+
+    fn push_word(&self, cap: &mut String, word: &str, force: bool)
+    {
+        let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+
+        if core.chars().skip(1).any(char::is_uppercase)
+        {
+            cap.push_str(word);
+        }
+        else if !force && self.style.is_stop_word(core)
+        {
+            cap.push_str(&word.to_lowercase());
+        }
+        else
+        {
+            // Opening punctuation such as quotes or brackets is skipped
+            let mut chars = word.chars();
+            for ch in chars.by_ref()
+            {
+                if ch.is_alphabetic()
+                {
+                    push_titlecase(cap, ch);
+                    break;
+                }
+                cap.push(ch);
+                if ch.is_alphanumeric()
+                {
+                    break;
+                }
+            }
+            cap.push_str(chars.as_str());
+        }
+    }
+}
+
+
+/// Capitalize every word with the default word boundaries.
+pub fn capitalize(s: &str) -> String
+{
+    Capitalizer::default().capitalize(s)
+}
+
+
+/// Title-case `s` following `style`, with the default word boundaries.
+pub fn title_case(s: &str, style: TitleCaseStyle) -> String
+{
+    Capitalizer::default().with_style(style).capitalize(s)
+}
@@ -1,32 +1,128 @@
-pub mod tests;
+use std::fs::{rename, set_permissions, File};
+use std::io::{self, stdin, stdout, BufReader, BufWriter};
+use std::path::PathBuf;
+use std::process::exit;
 
-use std::env::args;
+use clap::Parser;
 
+use capitalize::capitalize;
+use capitalize::case::{convert, Case};
+use capitalize::markup::MarkupCapitalizer;
+use capitalize::stream::transform_lines;
 
-fn capitalize(s: &str) -> String
+
+/// Capitalize every word of the text, or convert it to another case with `--to`.
+///
+/// Without TEXT the input file (or stdin) is processed line by line,
+/// keeping line endings and whitespace as they are.
+#[derive(Parser, Debug)]
+struct Arguments
 {
-    let mut char_array: Vec<_> = s.chars().collect();
-    for i in 0..char_array.len()
+    text: Option<String>,
+
+    /// Target case: snake, kebab, camel, pascal, constant, sentence or title
+    #[arg(long)]
+    to: Option<Case>,
+
+    /// Read from a file instead of stdin
+    #[arg(short, long, conflicts_with = "text")]
+    input: Option<PathBuf>,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long, conflicts_with_all = ["in_place", "text"])]
+    output: Option<PathBuf>,
+
+    /// Rewrite the input file
+    #[arg(long, requires = "input")]
+    in_place: bool,
+
+    /// Leave Markdown/HTML code, links, URLs and tags untouched
+    #[arg(long, conflicts_with = "to")]
+    markup: bool,
+
+    /// Extra regular expression to leave untouched in markup mode (repeatable)
+    #[arg(long, requires = "markup")]
+    protect: Vec<String>,
+}
+
+
+fn main()
+{
+    let args = Arguments::parse();
+
+    let mut markup = MarkupCapitalizer::default();
+    for pattern in args.protect.iter()
     {
-        if char_array[i].is_ascii_alphabetic()
+        markup = match markup.protect(pattern)
         {
-            if (i > 0 && char_array[i-1] == ' ') || i == 0
+            Ok(m) => m,
+            Err(e) =>
             {
-                char_array[i] = char_array[i].to_ascii_uppercase();
+                eprintln!("capitalize: invalid pattern {}: {}", pattern, e);
+                exit(1);
             }
+        };
+    }
+
+    let mut transform = |line: &str| match args.to
+    {
+        Some(case) => convert(line, case),
+        None if args.markup => markup.capitalize_line(line),
+        None => capitalize(line),
+    };
+
+    if let Some(text) = &args.text
+    {
+        match args.to
+        {
+            Some(_) => println!("{}", transform(text)),
+            None => println!("Capitalized text: {}", transform(text)),
         }
+        return;
+    }
+
+    if let Err(e) = run(&args, transform)
+    {
+        eprintln!("capitalize: {}", e);
+        exit(1);
     }
-    char_array.iter().collect()
 }
 
 
-fn main() 
+fn run(args: &Arguments, transform: impl FnMut(&str) -> String) -> io::Result<()>
 {
-    let args: Vec<String> = args().skip(1).collect();
-    assert!(args.len() > 0);
+    match (&args.input, &args.output, args.in_place)
+    {
+        (Some(input), _, true) =>
+        {
+            // Write next to the input and rename, so a failure leaves the input intact
+            let mut tmp = input.clone().into_os_string();
+            tmp.push(".capitalize.tmp");
+            let tmp = PathBuf::from(tmp);
 
-    let cap = capitalize(&args[0]);
-    println!("Capitalized text: {}", cap);
+            let file = File::open(input)?;
+            let permissions = file.metadata()?.permissions();
+            let result = transform_lines(BufReader::new(file), BufWriter::new(File::create(&tmp)?), transform)
+                .and_then(|_| set_permissions(&tmp, permissions))
+                .and_then(|_| rename(&tmp, input));
+            if result.is_err()
+            {
+                let _ = std::fs::remove_file(&tmp);
+            }
+            result
+        }
+        (input, output, _) =>
+        {
+            let reader: Box<dyn io::BufRead> = match input
+            {
+                Some(path) => Box::new(BufReader::new(File::open(path)?)),
+                None => Box::new(stdin().lock()),
+            };
+            match output
+            {
+                Some(path) => transform_lines(reader, BufWriter::new(File::create(path)?), transform),
+                None => transform_lines(reader, BufWriter::new(stdout().lock()), transform),
+            }
+        }
+    }
 }
-
-
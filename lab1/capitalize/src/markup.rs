@@ -0,0 +1,460 @@
+use regex::Regex;
+
+use crate::Capitalizer;
+
+
+/// Stands in for a protected segment while the text around it is capitalized.
+const PLACEHOLDER: char = '\u{E000}';
+
+/// Stands in for an entity encoding a letter, such as `&eacute;`: being a
+/// digit, it counts as the start of the word without being case-mapped.
+const LETTER_PLACEHOLDER: char = '\u{1D7CE}';
+
+/// Named entities that are not letters.
+const SYMBOL_ENTITIES: &[&str] = &[
+    "amp", "apos", "bull", "copy", "gt", "hellip", "laquo", "ldquo", "lsquo", "lt", "mdash",
+    "nbsp", "ndash", "quot", "raquo", "rdquo", "reg", "rsquo", "shy", "trade",
+];
+
+/// Elements whose content is code rather than prose.
+const RAW_ELEMENTS: &[&str] = &["code", "kbd", "pre", "samp", "script", "style"];
+
+const URL: &str = r#"\b[A-Za-z][A-Za-z0-9+.-]*://[^\s<>"]*[^\s<>".,;:!?)\]]"#;
+const WWW: &str = r#"\bwww\.[^\s<>"]*[^\s<>".,;:!?)\]]"#;
+const EMAIL: &str = r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b";
+
+
+/// Where the previous line left the tokenizer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum State
+{
+    #[default]
+    Text,
+    /// Inside a fenced code block opened by `len` `marker` characters.
+    Fence { marker: char, len: usize },
+    /// Inside an HTML comment.
+    Comment,
+    /// Inside a raw element, waiting for its closing tag.
+    Raw(String),
+}
+
+
+/// Capitalizes Markdown or HTML, leaving code, URLs, e-mail addresses and
+/// tags untouched.
+///
+/// Protected are fenced code blocks, code spans, link targets, HTML tags with
+/// their attributes, comments, entities and the content of elements such as
+/// `<pre>` and `<code>`. Further patterns can be added with [`Self::protect`].
+/// Lines must be fed in order, since blocks and comments span several lines.
+#[derive(Debug, Clone)]
+pub struct MarkupCapitalizer
+{
+    capitalizer: Capitalizer,
+    patterns: Vec<Regex>,
+    state: State,
+}
+
+
+impl Default for MarkupCapitalizer
+{
+    fn default() -> Self
+    {
+        Self::new(Capitalizer::default())
+    }
+}
+
+
+impl MarkupCapitalizer
+{
+    pub fn new(capitalizer: Capitalizer) -> Self
+    {
+        let patterns = [URL, WWW, EMAIL].iter().map(|p| Regex::new(p).unwrap()).collect();
+        Self { capitalizer, patterns, state: State::Text }
+    }
+
+
+    /// Also leave untouched any text matching `pattern`.
+    pub fn protect(mut self, pattern: &str) -> Result<Self, regex::Error>
+    {
+        self.patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+
+    /// Capitalize a whole document, keeping its line endings.
+    pub fn capitalize(&mut self, text: &str) -> String
+    {
+        let mut out = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n')
+        {
+            let content = line.trim_end_matches('\n').trim_end_matches('\r');
+            out.push_str(&self.capitalize_line(content));
+            out.push_str(&line[content.len()..]);
+        }
+        out
+    }
+
+
+    /// Capitalize one line, without its line ending.
+    pub fn capitalize_line(&mut self, line: &str) -> String
+    {
+        let mut masked = String::with_capacity(line.len());
+        let mut protected: Vec<&str> = Vec::new();
+
+        for (start, end, placeholder) in self.split_line(line)
+        {
+            match placeholder
+            {
+                Some(p) =>
+                {
+                    masked.push(p);
+                    protected.push(&line[start..end]);
+                }
+                None => masked.push_str(&line[start..end]),
+            }
+        }
+
+        let mut segments = protected.iter();
+        let mut out = String::with_capacity(line.len());
+        for ch in self.capitalizer.capitalize(&masked).chars()
+        {
+            if ch == PLACEHOLDER || ch == LETTER_PLACEHOLDER
+            {
+                out.push_str(segments.next().unwrap());
+            }
+            else
+            {
+                out.push(ch);
+            }
+        }
+        out
+    }
+
+
+    /// Byte ranges of `line`, with the placeholder of the protected ones.
+    fn split_line(&mut self, line: &str) -> Vec<(usize, usize, Option<char>)>
+    {
+        if let State::Fence { marker, len } = self.state
+        {
+            if fence(line).is_some_and(|(m, l)| m == marker && l >= len && line.trim().len() == l)
+            {
+                self.state = State::Text;
+            }
+            return vec![(0, line.len(), Some(PLACEHOLDER))];
+        }
+        if self.state == State::Text
+        {
+            if let Some((marker, len)) = fence(line)
+            {
+                self.state = State::Fence { marker, len };
+                return vec![(0, line.len(), Some(PLACEHOLDER))];
+            }
+        }
+
+        let mut spans: Vec<(usize, usize, char)> = Vec::new();
+        let mut i = 0;
+        while i < line.len()
+        {
+            match self.protected_span(line, i)
+            {
+                Some((end, placeholder)) =>
+                {
+                    spans.push((i, end, placeholder));
+                    i = end;
+                }
+                None => i += line[i..].chars().next().unwrap().len_utf8(),
+            }
+        }
+
+        let mut segments = Vec::new();
+        let mut text_start = 0;
+        for (start, end, placeholder) in spans
+        {
+            self.push_text(line, text_start, start, &mut segments);
+            segments.push((start, end, Some(placeholder)));
+            text_start = end;
+        }
+        self.push_text(line, text_start, line.len(), &mut segments);
+        segments
+    }
+
+
+    /// Split the text between `start` and `end` on the protected patterns.
+    fn push_text(&self, line: &str, start: usize, end: usize, segments: &mut Vec<(usize, usize, Option<char>)>)
+    {
+        let text = &line[start..end];
+        let mut matches: Vec<(usize, usize)> = self.patterns.iter()
+            .flat_map(|p| p.find_iter(text).map(|m| (m.start(), m.end())))
+            .collect();
+        matches.sort();
+
+        let mut pos = 0;
+        for (m_start, m_end) in matches
+        {
+            if m_start < pos
+            {
+                continue;
+            }
+            if m_start > pos
+            {
+                segments.push((start + pos, start + m_start, None));
+            }
+            segments.push((start + m_start, start + m_end, Some(PLACEHOLDER)));
+            pos = m_end;
+        }
+        if pos < text.len()
+        {
+            segments.push((start + pos, end, None));
+        }
+    }
+
+
+    /// End and placeholder of the protected span starting at byte `i`, if there is one.
+    fn protected_span(&mut self, line: &str, i: usize) -> Option<(usize, char)>
+    {
+        self.protected_end(line, i)
+            .map(|end| (end, if is_letter_entity(&line[i..end]) { LETTER_PLACEHOLDER } else { PLACEHOLDER }))
+    }
+
+
+    fn protected_end(&mut self, line: &str, i: usize) -> Option<usize>
+    {
+        let rest = &line[i..];
+
+        match &self.state
+        {
+            State::Comment => return Some(self.close(line, i, "-->")),
+            State::Raw(name) =>
+            {
+                let closing = format!("</{}", name);
+                match rest.to_ascii_lowercase().find(&closing)
+                {
+                    // The closing tag itself is handled below
+                    Some(0) => self.state = State::Text,
+                    Some(pos) => return Some(i + pos),
+                    None => return Some(line.len()),
+                }
+            }
+            State::Text | State::Fence { .. } => {}
+        }
+
+        if rest.starts_with(PLACEHOLDER)
+        {
+            return Some(i + PLACEHOLDER.len_utf8());
+        }
+        if rest.starts_with(LETTER_PLACEHOLDER)
+        {
+            return Some(i + LETTER_PLACEHOLDER.len_utf8());
+        }
+        if rest.starts_with("<!--")
+        {
+            self.state = State::Comment;
+            return Some(self.close(line, i + 4, "-->"));
+        }
+        if rest.starts_with('<')
+        {
+            return tag(rest).map(|len| self.enter(line, i, i + len));
+        }
+        if rest.starts_with('`')
+        {
+            return code_span(rest).map(|len| i + len);
+        }
+        if rest.starts_with("](") || rest.starts_with("][")
+        {
+            let (open, close) = if rest.starts_with("](") { ('(', ')') } else { ('[', ']') };
+            return matching(&rest[1..], open, close).map(|len| i + 1 + len);
+        }
+        if rest.starts_with('&')
+        {
+            return entity(rest).map(|len| i + len);
+        }
+        None
+    }
+
+
+    /// Protect up to and including `terminator`, or the whole line if it is not there.
+    fn close(&mut self, line: &str, from: usize, terminator: &str) -> usize
+    {
+        match line[from..].find(terminator)
+        {
+            Some(pos) =>
+            {
+                self.state = State::Text;
+                from + pos + terminator.len()
+            }
+            None => line.len(),
+        }
+    }
+
+
+    /// Protect the tag from `i` to `end`, entering raw elements such as `<pre>`.
+    fn enter(&mut self, line: &str, i: usize, end: usize) -> usize
+    {
+        let tag = &line[i + 1..end];
+        let name: String = tag.chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        if RAW_ELEMENTS.contains(&name.as_str()) && !tag.ends_with("/>")
+        {
+            self.state = State::Raw(name);
+        }
+        end
+    }
+}
+
+
+/// Length of the tag at the start of `rest`: `<`, a name, attributes and
+/// `>`, all on the line. Anything else, as in `a<b`, is a `<` in the text.
+fn tag(rest: &str) -> Option<usize>
+{
+    let bytes = rest.as_bytes();
+    let closing = bytes.get(1) == Some(&b'/');
+    let mut pos = 1 + closing as usize;
+    let declaration = !closing && matches!(bytes.get(pos), Some(b'!' | b'?'));
+    pos += declaration as usize;
+    if !bytes.get(pos)?.is_ascii_alphabetic()
+    {
+        return None;
+    }
+    if declaration
+    {
+        // Such as <!DOCTYPE html>, with no attributes to check
+        return rest[pos..].find('>').map(|p| pos + p + 1);
+    }
+    pos += bytes[pos..].iter().take_while(|b| b.is_ascii_alphanumeric() || b"-:_.".contains(b)).count();
+
+    loop
+    {
+        let spaces = bytes[pos..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+        pos += spaces;
+        match bytes.get(pos)?
+        {
+            b'>' => return Some(pos + 1),
+            b'/' if !closing && bytes.get(pos + 1) == Some(&b'>') => return Some(pos + 2),
+            // Attributes come after a space, and never in closing tags
+            _ if closing || spaces == 0 => return None,
+            _ => {}
+        }
+
+        let name = bytes[pos..].iter().take_while(|b| !b.is_ascii_whitespace() && !b"\"'<>/=".contains(b)).count();
+        if name == 0
+        {
+            return None;
+        }
+        pos += name;
+        let before = bytes[pos..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+        if bytes.get(pos + before) != Some(&b'=')
+        {
+            continue;
+        }
+        pos += before + 1;
+        pos += bytes[pos..].iter().take_while(|b| b.is_ascii_whitespace()).count();
+        match *bytes.get(pos)?
+        {
+            quote @ (b'"' | b'\'') => pos += 2 + rest[pos + 1..].find(quote as char)?,
+            _ =>
+            {
+                let value = bytes[pos..].iter().take_while(|b| !b.is_ascii_whitespace() && !b"\"'=<>`".contains(b)).count();
+                if value == 0
+                {
+                    return None;
+                }
+                pos += value;
+            }
+        }
+    }
+}
+
+
+/// Marker character and length of a code fence opening or closing `line`.
+fn fence(line: &str) -> Option<(char, usize)>
+{
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let rest = &line[indent..];
+    let marker = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = rest.chars().take_while(|c| *c == marker).count();
+
+    (indent <= 3 && len >= 3).then_some((marker, len))
+}
+
+
+/// Length of the code span at the start of `rest`, closed by a backtick run of the same length.
+fn code_span(rest: &str) -> Option<usize>
+{
+    let ticks = rest.chars().take_while(|c| *c == '`').count();
+    let mut pos = ticks;
+    while let Some(found) = rest[pos..].find('`')
+    {
+        let start = pos + found;
+        let run = rest[start..].chars().take_while(|c| *c == '`').count();
+        if run == ticks
+        {
+            return Some(start + run);
+        }
+        pos = start + run;
+    }
+    None
+}
+
+
+/// Length of the bracketed group at the start of `rest`, nested brackets included.
+fn matching(rest: &str, open: char, close: char) -> Option<usize>
+{
+    let mut depth = 0;
+    for (pos, ch) in rest.char_indices()
+    {
+        if ch == open
+        {
+            depth += 1;
+        }
+        else if ch == close
+        {
+            depth -= 1;
+            if depth == 0
+            {
+                return Some(pos + 1);
+            }
+        }
+    }
+    None
+}
+
+
+/// Whether `span` is an entity encoding a letter, or a literal letter placeholder.
+fn is_letter_entity(span: &str) -> bool
+{
+    if span.starts_with(LETTER_PLACEHOLDER)
+    {
+        return true;
+    }
+    let Some(name) = span.strip_prefix('&').and_then(|s| s.strip_suffix(';'))
+    else
+    {
+        return false;
+    };
+
+    match name.strip_prefix('#')
+    {
+        Some(code) =>
+        {
+            let value = match code.strip_prefix(['x', 'X'])
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => code.parse::<u32>().ok(),
+            };
+            value.and_then(char::from_u32).is_some_and(char::is_alphabetic)
+        }
+        None => !SYMBOL_ENTITIES.contains(&name),
+    }
+}
+
+
+/// Length of the HTML entity (`&amp;`, `&#233;`) at the start of `rest`.
+fn entity(rest: &str) -> Option<usize>
+{
+    let body = rest[1..].strip_prefix('#').unwrap_or(&rest[1..]);
+    let len = body.chars().take_while(|c| c.is_ascii_alphanumeric()).count();
+
+    (len > 0 && body[len..].starts_with(';')).then_some(rest.len() - body.len() + len + 1)
+}
@@ -0,0 +1,42 @@
+use std::io::{self, BufRead, Write};
+
+
+/// Apply `transform` to every line of `input`, writing the result to `output`.
+///
+/// Only one line is held in memory at a time. Line endings (`\n` or `\r\n`)
+/// are copied unchanged, including a missing newline at the end of the input.
+pub fn transform_lines<R, W, F>(mut input: R, mut output: W, mut transform: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> String,
+{
+    let mut buf: Vec<u8> = Vec::new();
+    let mut line_no: usize = 0;
+
+    loop
+    {
+        buf.clear();
+        if input.read_until(b'\n', &mut buf)? == 0
+        {
+            break;
+        }
+        line_no += 1;
+
+        let mut end = buf.len();
+        if buf.ends_with(b"\r\n")
+        {
+            end -= 2;
+        }
+        else if buf.ends_with(b"\n")
+        {
+            end -= 1;
+        }
+
+        let line = std::str::from_utf8(&buf[..end])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_no, e)))?;
+        output.write_all(transform(line).as_bytes())?;
+        output.write_all(&buf[end..])?;
+    }
+    output.flush()
+}
@@ -0,0 +1,60 @@
+/// Which words of a title stay lowercase.
+///
+/// The first and last word are always capitalized, whatever the style.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TitleCaseStyle
+{
+    /// Every word is capitalized.
+    #[default]
+    Simple,
+    /// AP: articles, conjunctions and prepositions of up to three letters.
+    Ap,
+    /// Chicago: articles, coordinating conjunctions and every preposition.
+    Chicago,
+    /// APA: articles, conjunctions and prepositions of up to three letters.
+    Apa,
+}
+
+
+const AP: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", "off", "on",
+    "or", "per", "so", "the", "to", "up", "via", "yet",
+];
+
+const CHICAGO: &[&str] = &[
+    "a", "about", "above", "across", "after", "against", "along", "among", "an", "and",
+    "around", "as", "at", "before", "behind", "below", "beneath", "beside", "between",
+    "beyond", "but", "by", "down", "during", "except", "for", "from", "in", "inside",
+    "into", "like", "near", "nor", "of", "off", "on", "onto", "or", "out", "outside",
+    "over", "past", "per", "since", "the", "through", "throughout", "till", "to",
+    "toward", "towards", "under", "underneath", "until", "up", "upon", "via", "with",
+    "within", "without",
+];
+
+const APA: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "if", "in", "nor", "of", "off",
+    "on", "or", "per", "so", "the", "to", "up", "via", "yet",
+];
+
+
+impl TitleCaseStyle
+{
+    /// Lowercase words of the style, sorted.
+    pub fn stop_words(&self) -> &'static [&'static str]
+    {
+        match self
+        {
+            TitleCaseStyle::Simple => &[],
+            TitleCaseStyle::Ap => AP,
+            TitleCaseStyle::Chicago => CHICAGO,
+            TitleCaseStyle::Apa => APA,
+        }
+    }
+
+
+    /// Whether `word`, compared case-insensitively, stays lowercase inside a title.
+    pub fn is_stop_word(&self, word: &str) -> bool
+    {
+        self.stop_words().binary_search(&word.to_lowercase().as_str()).is_ok()
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod tests
 {
-    use crate::capitalize;
+    use crate::{capitalize, title_case, Capitalizer, TitleCaseStyle};
+    use crate::case::{convert, split_words, Case};
+    use crate::markup::MarkupCapitalizer;
+    use crate::stream::transform_lines;
+    use crate::titlecase::to_titlecase;
 
     #[test]
     fn more_than_one_word()
@@ -21,7 +25,7 @@ mod tests
     fn begin_with_accent()
     {
         let s = "è una stringa con è";
-        assert_eq!("È una stringa con È", capitalize(s));
+        assert_eq!("È Una Stringa Con È", capitalize(s));
     }
 
     #[test]
@@ -37,4 +41,315 @@ mod tests
         let s = "i  have multiple     spaces";
         assert_eq!("I  Have Multiple     Spaces", capitalize(s));
     }
+
+    #[test]
+    fn accented_letters()
+    {
+        let s = "élan vital";
+        assert_eq!("Élan Vital", capitalize(s));
+    }
+
+    #[test]
+    fn any_whitespace_separates_words()
+    {
+        let s = "tab\tnew\nline\u{00A0}nbsp";
+        assert_eq!("Tab\tNew\nLine\u{00A0}Nbsp", capitalize(s));
+    }
+
+    #[test]
+    fn hyphen_and_slash_separate_words()
+    {
+        let s = "jean-luc and/or o'neil";
+        assert_eq!("Jean-Luc And/Or O'neil", capitalize(s));
+    }
+
+    #[test]
+    fn configurable_boundaries()
+    {
+        let cap = Capitalizer::new(&['\'']);
+        assert_eq!("O'Neil Jean-luc", cap.capitalize("o'neil jean-luc"));
+    }
+
+    #[test]
+    fn multi_char_titlecase()
+    {
+        assert_eq!("Ss", to_titlecase('ß'));
+        assert_eq!("Ssa", capitalize("ßa"));
+        assert_eq!("\u{01C5}emal", capitalize("\u{01C6}emal"));
+        assert_eq!("Ffi", to_titlecase('\u{FB03}'));
+        assert_eq!("\u{1F88}", to_titlecase('\u{1F80}'));
+    }
+
+    #[test]
+    fn rest_of_word_is_untouched()
+    {
+        let s = "mcdonald's o'brien";
+        assert_eq!("Mcdonald's O'brien", capitalize(s));
+    }
+
+    #[test]
+    fn acronyms_and_brands_are_preserved()
+    {
+        let s = "the NASA iPhone and eBay";
+        assert_eq!("The NASA iPhone And eBay", capitalize(s));
+    }
+
+    #[test]
+    fn leading_punctuation_is_skipped()
+    {
+        let s = "\"quoted\" (words)";
+        assert_eq!("\"Quoted\" (Words)", capitalize(s));
+    }
+
+    #[test]
+    fn stop_word_lists_are_sorted()
+    {
+        for style in [TitleCaseStyle::Simple, TitleCaseStyle::Ap, TitleCaseStyle::Chicago, TitleCaseStyle::Apa]
+        {
+            assert!(style.stop_words().windows(2).all(|w| w[0] < w[1]), "{:?}", style);
+        }
+    }
+
+    #[test]
+    fn simple_style_capitalizes_every_word()
+    {
+        let s = "the lord of the rings";
+        assert_eq!("The Lord Of The Rings", title_case(s, TitleCaseStyle::Simple));
+    }
+
+    #[test]
+    fn ap_style()
+    {
+        let s = "the lord of the rings";
+        assert_eq!("The Lord of the Rings", title_case(s, TitleCaseStyle::Ap));
+        let s = "a walk through the woods with a dog";
+        assert_eq!("A Walk Through the Woods With a Dog", title_case(s, TitleCaseStyle::Ap));
+    }
+
+    #[test]
+    fn chicago_style_lowercases_long_prepositions()
+    {
+        let s = "a walk through the woods with a dog";
+        assert_eq!("A Walk through the Woods with a Dog", title_case(s, TitleCaseStyle::Chicago));
+    }
+
+    #[test]
+    fn apa_style()
+    {
+        let s = "what if the sky falls";
+        assert_eq!("What if the Sky Falls", title_case(s, TitleCaseStyle::Apa));
+        assert_eq!("What If the Sky Falls", title_case(s, TitleCaseStyle::Ap));
+    }
+
+    #[test]
+    fn first_and_last_words_are_always_capitalized()
+    {
+        let s = "of mice and men to";
+        assert_eq!("Of Mice and Men To", title_case(s, TitleCaseStyle::Chicago));
+    }
+
+    #[test]
+    fn stop_words_are_lowercased()
+    {
+        let s = "Gone With The Wind";
+        assert_eq!("Gone with the Wind", title_case(s, TitleCaseStyle::Chicago));
+    }
+
+    #[test]
+    fn subtitle_after_colon_is_capitalized()
+    {
+        let s = "star wars: a new hope";
+        assert_eq!("Star Wars: A New Hope", title_case(s, TitleCaseStyle::Ap));
+    }
+
+    #[test]
+    fn acronyms_are_preserved_in_styles()
+    {
+        let s = "the iPhone of the USA";
+        assert_eq!("The iPhone of the USA", title_case(s, TitleCaseStyle::Chicago));
+    }
+
+    #[test]
+    fn hyphenated_compounds()
+    {
+        let s = "state-of-the-art design";
+        assert_eq!("State-of-the-Art Design", title_case(s, TitleCaseStyle::Chicago));
+    }
+
+    #[test]
+    fn words_starting_with_digits()
+    {
+        let s = "1st place";
+        assert_eq!("1st Place", capitalize(s));
+    }
+
+    #[test]
+    fn split_on_separators()
+    {
+        assert_eq!(vec!["hello", "big", "World"], split_words("hello_big-World"));
+        assert_eq!(vec!["one", "two"], split_words("  one   two  "));
+    }
+
+    #[test]
+    fn split_camel_humps_and_acronyms()
+    {
+        assert_eq!(vec!["parse", "HTTP", "Response"], split_words("parseHTTPResponse"));
+        assert_eq!(vec!["XML", "Http", "Request"], split_words("XMLHttpRequest"));
+        assert_eq!(vec!["my", "Var"], split_words("myVar"));
+    }
+
+    #[test]
+    fn split_keeps_digits_with_previous_letters()
+    {
+        assert_eq!(vec!["utf8", "Encoder"], split_words("utf8Encoder"));
+        assert_eq!(vec!["HTTP2", "Server"], split_words("HTTP2Server"));
+        assert_eq!(vec!["version", "2"], split_words("version_2"));
+    }
+
+    #[test]
+    fn convert_to_every_case()
+    {
+        let s = "parseHTTPResponse body";
+        assert_eq!("parse_http_response_body", convert(s, Case::Snake));
+        assert_eq!("parse-http-response-body", convert(s, Case::Kebab));
+        assert_eq!("parseHttpResponseBody", convert(s, Case::Camel));
+        assert_eq!("ParseHttpResponseBody", convert(s, Case::Pascal));
+        assert_eq!("PARSE_HTTP_RESPONSE_BODY", convert(s, Case::Constant));
+        assert_eq!("Parse http response body", convert(s, Case::Sentence));
+        assert_eq!("Parse Http Response Body", convert(s, Case::Title));
+    }
+
+    #[test]
+    fn convert_unicode_words()
+    {
+        assert_eq!("ÉlanVital", convert("élan vital", Case::Pascal));
+        assert_eq!("straße_nr", convert("StraßeNr", Case::Snake));
+    }
+
+    #[test]
+    fn convert_empty_string()
+    {
+        assert_eq!("", convert("", Case::Camel));
+        assert_eq!("", convert(" - ", Case::Snake));
+    }
+
+    #[test]
+    fn case_names()
+    {
+        assert_eq!(Ok(Case::Constant), "constant".parse());
+        assert_eq!(Ok(Case::Pascal), "Pascal".parse());
+        assert!("upper".parse::<Case>().is_err());
+        assert_eq!("kebab", Case::Kebab.to_string());
+    }
+
+    fn transform(input: &str) -> String
+    {
+        let mut out: Vec<u8> = Vec::new();
+        transform_lines(input.as_bytes(), &mut out, capitalize).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn stream_preserves_line_endings()
+    {
+        assert_eq!("One\nTwo\r\nThree", transform("one\ntwo\r\nthree"));
+        assert_eq!("One\n\n", transform("one\n\n"));
+        assert_eq!("", transform(""));
+    }
+
+    #[test]
+    fn stream_preserves_whitespace()
+    {
+        assert_eq!("  Indented\tTab  \n", transform("  indented\ttab  \n"));
+    }
+
+    #[test]
+    fn stream_rejects_invalid_utf8()
+    {
+        let mut out: Vec<u8> = Vec::new();
+        let err = transform_lines(&b"ok\n\xff\n"[..], &mut out, capitalize).unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert!(err.to_string().starts_with("line 2"));
+    }
+
+    fn markup(text: &str) -> String
+    {
+        MarkupCapitalizer::default().capitalize(text)
+    }
+
+    #[test]
+    fn markup_skips_code_spans()
+    {
+        assert_eq!("Call `foo bar` Now", markup("call `foo bar` now"));
+        assert_eq!("Use ``a ` b`` Here", markup("use ``a ` b`` here"));
+    }
+
+    #[test]
+    fn markup_skips_fenced_blocks()
+    {
+        let text = "intro text\n```rust\nlet x = foo();\n```\nafter block\n";
+        assert_eq!("Intro Text\n```rust\nlet x = foo();\n```\nAfter Block\n", markup(text));
+    }
+
+    #[test]
+    fn markup_skips_link_targets_and_urls()
+    {
+        assert_eq!("[Click Here](http://example.com/some-page) Please",
+            markup("[click here](http://example.com/some-page) please"));
+        assert_eq!("See https://example.com/path, Then www.example.org.",
+            markup("see https://example.com/path, then www.example.org."));
+        assert_eq!("Mail john.doe@example.com Today", markup("mail john.doe@example.com today"));
+    }
+
+    #[test]
+    fn markup_skips_tags_and_attributes()
+    {
+        assert_eq!("<a href=\"/some/path\" title=\"keep me\">Link Text</a> And <b>Bold</b>",
+            markup("<a href=\"/some/path\" title=\"keep me\">link text</a> and <b>bold</b>"));
+    }
+
+    #[test]
+    fn markup_skips_raw_elements_and_comments()
+    {
+        let text = "<pre>\nraw text\n</pre>\nsome text <!-- a\ncomment --> more\n<code>x y</code> z";
+        assert_eq!("<pre>\nraw text\n</pre>\nSome Text <!-- a\ncomment --> More\n<code>x y</code> Z", markup(text));
+    }
+
+    #[test]
+    fn markup_skips_entities()
+    {
+        assert_eq!("Fish &amp; Chips &#233;t&eacute;", markup("fish &amp; chips &#233;t&eacute;"));
+        assert_eq!("&quot;Quoted&quot; &#x4E;", markup("&quot;quoted&quot; &#x4E;"));
+    }
+
+    #[test]
+    fn markup_tags_close_on_their_line()
+    {
+        assert_eq!("<img src=\"a b\" alt=x/> Caption <br> End", markup("<img src=\"a b\" alt=x/> caption <br> end"));
+        assert_eq!("<!DOCTYPE html>\n</p > Text", markup("<!DOCTYPE html>\n</p > text"));
+        // Without its `>` on the line a `<` is text, and so is what follows
+        assert_eq!("<Img\nNext Line", markup("<img\nnext line"));
+    }
+
+    #[test]
+    fn markup_bare_less_than_is_text()
+    {
+        assert_eq!("If A<b Then Swap\nAnd Go On", markup("if a<b then swap\nand go on"));
+        assert_eq!("Use X < Y And A<3 Here", markup("use x < y and a<3 here"));
+    }
+
+    #[test]
+    fn markup_custom_patterns()
+    {
+        let mut cap = MarkupCapitalizer::default().protect(r"\$\w+").unwrap();
+        assert_eq!("Set $home Now", cap.capitalize("set $home now"));
+        assert!(MarkupCapitalizer::default().protect("(").is_err());
+    }
+
+    #[test]
+    fn markup_keeps_placeholder_characters()
+    {
+        assert_eq!("A \u{E000} `b` C", markup("a \u{E000} `b` c"));
+        assert_eq!("\u{1D7CE}x `b` C", markup("\u{1D7CE}x `b` c"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+/// Characters whose titlecase mapping differs from `char::to_uppercase`,
+/// taken from UnicodeData.txt and SpecialCasing.txt. Sorted by character.
+const SPECIAL: &[(char, &str)] = &[
+    ('\u{00DF}', "Ss"),
+    ('\u{01C4}', "\u{01C5}"),
+    ('\u{01C5}', "\u{01C5}"),
+    ('\u{01C6}', "\u{01C5}"),
+    ('\u{01C7}', "\u{01C8}"),
+    ('\u{01C8}', "\u{01C8}"),
+    ('\u{01C9}', "\u{01C8}"),
+    ('\u{01CA}', "\u{01CB}"),
+    ('\u{01CB}', "\u{01CB}"),
+    ('\u{01CC}', "\u{01CB}"),
+    ('\u{01F1}', "\u{01F2}"),
+    ('\u{01F2}', "\u{01F2}"),
+    ('\u{01F3}', "\u{01F2}"),
+    ('\u{0587}', "\u{0535}\u{0582}"),
+    ('\u{1FB2}', "\u{1FBA}\u{0345}"),
+    ('\u{1FB3}', "\u{1FBC}"),
+    ('\u{1FB4}', "\u{0386}\u{0345}"),
+    ('\u{1FB7}', "\u{0391}\u{0342}\u{0345}"),
+    ('\u{1FBC}', "\u{1FBC}"),
+    ('\u{1FC2}', "\u{1FCA}\u{0345}"),
+    ('\u{1FC3}', "\u{1FCC}"),
+    ('\u{1FC4}', "\u{0389}\u{0345}"),
+    ('\u{1FC7}', "\u{0397}\u{0342}\u{0345}"),
+    ('\u{1FCC}', "\u{1FCC}"),
+    ('\u{1FF2}', "\u{1FFA}\u{0345}"),
+    ('\u{1FF3}', "\u{1FFC}"),
+    ('\u{1FF4}', "\u{038F}\u{0345}"),
+    ('\u{1FF7}', "\u{03A9}\u{0342}\u{0345}"),
+    ('\u{1FFC}', "\u{1FFC}"),
+    ('\u{FB00}', "Ff"),
+    ('\u{FB01}', "Fi"),
+    ('\u{FB02}', "Fl"),
+    ('\u{FB03}', "Ffi"),
+    ('\u{FB04}', "Ffl"),
+    ('\u{FB05}', "St"),
+    ('\u{FB06}', "St"),
+    ('\u{FB13}', "\u{0544}\u{0576}"),
+    ('\u{FB14}', "\u{0544}\u{0565}"),
+    ('\u{FB15}', "\u{0544}\u{056B}"),
+    ('\u{FB16}', "\u{054E}\u{0576}"),
+    ('\u{FB17}', "\u{0544}\u{056D}"),
+];
+
+
+/// Append the titlecase mapping of `ch`, which may be more than one character.
+pub fn push_titlecase(out: &mut String, ch: char)
+{
+    match ch
+    {
+        // Greek letters with ypogegrammeni: the titlecase form is the prosgegrammeni one
+        '\u{1F80}'..='\u{1FAF}' => out.push(char::from_u32(ch as u32 | 0x08).unwrap()),
+        _ => match SPECIAL.binary_search_by_key(&ch, |(c, _)| *c)
+        {
+            Ok(i) => out.push_str(SPECIAL[i].1),
+            Err(_) => out.extend(ch.to_uppercase()),
+        },
+    }
+}
+
+
+/// The titlecase mapping of `ch`, e.g. `ǆ` becomes `ǅ` and `ß` becomes `Ss`.
+pub fn to_titlecase(ch: char) -> String
+{
+    let mut title = String::new();
+    push_titlecase(&mut title, ch);
+    title
+}
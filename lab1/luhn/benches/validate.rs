@@ -0,0 +1,109 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use luhn::{bytes, complete, validate};
+
+const CODES: usize = 1_000_000;
+
+
+/// The original `is_valid`, verbatim, kept as a baseline.
+#[allow(unused_mut, clippy::needless_bool, clippy::needless_return)]
+fn legacy_is_valid(code: &str) -> bool 
+{
+    if code.len() <= 1
+    {
+        println!("Code is too short!");
+        return false;
+    }
+    else 
+    {
+        let mut code_ch: Vec<char> = 
+            code.chars()
+                .filter(|c| *c != ' ')
+                .collect();
+
+        if code_ch.len() <= 1
+        {
+            println!("Code is too short!");
+            return false;
+        }
+        else
+        {
+            match code_ch.iter().find(|c| !(*c).is_numeric())
+            {
+                None => {},
+                Some(_) => 
+                {
+                    println!("Invalid format");
+                    return false;
+                },
+            }
+            let mut code_int: Vec<u32> = code_ch.iter()
+                .map(|c| c.to_digit(10).unwrap())
+                .rev()
+                .collect();
+
+            let mut sum: u32 = 0;
+            let mut rep: u32;
+            for i in (0..code_int.len() - 1).step_by(2)
+            {
+                rep = 2 * code_int[i+1];
+                if rep > 9
+                {
+                    rep -= 9;
+                }
+                sum = sum + code_int[i] + rep;
+            }
+
+            if sum % 10 == 0 {true} else {false}
+        }  
+    }
+}
+
+
+/// Half valid card numbers in groups of four, half with the check digit off by one.
+fn codes() -> Vec<String>
+{
+    let mut seed: u64 = 42;
+    (0..CODES)
+        .map(|i|
+        {
+            let mut payload = String::new();
+            for _ in 0..15
+            {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                payload.push(char::from(b'0' + (seed >> 60) as u8 % 10));
+            }
+            let mut digits = complete(&payload).unwrap().digits().to_string();
+            if i % 2 == 1
+            {
+                let last = digits.pop().unwrap().to_digit(10).unwrap();
+                digits.push(char::from_digit((last + 1) % 10, 10).unwrap());
+            }
+            let groups: Vec<&str> = (0..digits.len()).step_by(4).map(|j| &digits[j..j + 4]).collect();
+            groups.join(" ")
+        })
+        .collect()
+}
+
+
+fn bench_validate(c: &mut Criterion)
+{
+    let codes = codes();
+    let mut group = c.benchmark_group("validate");
+    group.throughput(Throughput::Elements(CODES as u64));
+    group.sample_size(10);
+
+    group.bench_function("legacy_vec", |b| b.iter(||
+        codes.iter().filter(|c| legacy_is_valid(black_box(c))).count()));
+    group.bench_function("validate", |b| b.iter(||
+        codes.iter().filter(|c| validate(black_box(c)).is_ok()).count()));
+    group.bench_function("bytes_check", |b| b.iter(||
+        codes.iter().filter(|c| bytes::check(black_box(c)).is_ok()).count()));
+    group.bench_function("bytes_is_valid", |b| b.iter(||
+        codes.iter().filter(|c| bytes::is_valid(black_box(c.as_bytes()))).count()));
+    group.finish();
+}
+
+
+criterion_group!(benches, bench_validate);
+criterion_main!(benches);
@@ -0,0 +1,128 @@
+use crate::bytes::add_mod10;
+use crate::{double, LuhnError};
+
+
+/// Luhn checksum fed one digit, character or chunk at a time.
+///
+/// Like [`crate::bytes`] it keeps both possible sums, one per parity of the
+/// final length, so the state is always ready to be checked. Spaces are
+/// skipped; any other non-digit is rejected without changing the state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LuhnAccumulator
+{
+    /// Digits accepted so far.
+    count: usize,
+    /// Characters consumed so far, spaces included; used for error positions.
+    position: usize,
+    /// `sums[k]` is the sum mod 10 if the last digit has index parity `k`.
+    sums: [u32; 2],
+}
+
+
+impl LuhnAccumulator
+{
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+
+    /// Forget everything fed so far.
+    pub fn reset(&mut self)
+    {
+        *self = Self::default();
+    }
+
+
+    /// Add a digit in `0..=9`.
+    pub fn push_digit(&mut self, digit: u32) -> Result<(), LuhnError>
+    {
+        match char::from_digit(digit, 10)
+        {
+            Some(ch) => self.push(ch),
+            None => Err(LuhnError::InvalidCharacter
+            {
+                ch: char::REPLACEMENT_CHARACTER,
+                position: self.position,
+            }),
+        }
+    }
+
+
+    /// Add a character; spaces are skipped.
+    pub fn push(&mut self, ch: char) -> Result<(), LuhnError>
+    {
+        match ch
+        {
+            ' ' => {}
+            '0'..='9' =>
+            {
+                let d = ch as u32 - '0' as u32;
+                let k = self.count % 2;
+                self.sums[k] = add_mod10(self.sums[k], d);
+                self.sums[1 - k] = add_mod10(self.sums[1 - k], double(d));
+                self.count += 1;
+            }
+            _ => return Err(LuhnError::InvalidCharacter { ch, position: self.position }),
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+
+    /// Add every character of `chunk`, stopping at the first invalid one;
+    /// the characters before it are kept.
+    pub fn push_str(&mut self, chunk: &str) -> Result<(), LuhnError>
+    {
+        chunk.chars().try_for_each(|ch| self.push(ch))
+    }
+
+
+    /// Number of digits accepted so far.
+    pub fn len(&self) -> usize
+    {
+        self.count
+    }
+
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.count == 0
+    }
+
+
+    /// Current checksum remainder, taking the last digit as the check digit.
+    pub fn remainder(&self) -> u32
+    {
+        if self.count == 0 { 0 } else { self.sums[(self.count - 1) % 2] }
+    }
+
+
+    /// Check the digits fed so far, as [`crate::is_valid`] would.
+    pub fn check(&self) -> Result<(), LuhnError>
+    {
+        if self.count <= 1
+        {
+            return Err(LuhnError::TooShort);
+        }
+        match self.remainder()
+        {
+            0 => Ok(()),
+            actual => Err(LuhnError::ChecksumMismatch { expected: 0, actual }),
+        }
+    }
+
+
+    pub fn is_valid(&self) -> bool
+    {
+        self.check().is_ok()
+    }
+
+
+    /// The digit that, pushed next, would make the code valid
+    /// (a single digit is never valid, so this needs at least one digit first).
+    pub fn completing_digit(&self) -> u32
+    {
+        (10 - self.sums[self.count % 2]) % 10
+    }
+}
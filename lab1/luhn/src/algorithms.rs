@@ -0,0 +1,339 @@
+use crate::{luhn_sum, LuhnCode, LuhnError};
+
+
+/// A check-digit scheme validated over a normalised code.
+///
+/// Normalisation is shared by every algorithm: separators are removed,
+/// ASCII letters are upper-cased and each remaining character is checked
+/// with [`ChecksumAlgorithm::accepts`].
+pub trait ChecksumAlgorithm
+{
+    /// Name used to select the algorithm, e.g. from the command line.
+    fn name(&self) -> &'static str;
+
+    /// Remainder of the checksum of an already normalised code.
+    fn remainder(&self, code: &[char]) -> u32;
+
+    /// Remainder a valid code must have.
+    fn expected(&self) -> u32
+    {
+        0
+    }
+
+    /// Whether `ch` may appear at `index` of a normalised code of `len` characters.
+    fn accepts(&self, ch: char, _index: usize, _len: usize) -> bool
+    {
+        ch.is_ascii_digit()
+    }
+
+    /// Whether a normalised code of `len` characters can be valid.
+    fn accepts_len(&self, _len: usize) -> bool
+    {
+        true
+    }
+
+    /// Characters ignored in the input.
+    fn separators(&self) -> &'static [char]
+    {
+        &[' ']
+    }
+
+    /// Minimum number of characters, checked before looking at them.
+    fn min_len(&self) -> usize
+    {
+        2
+    }
+
+    fn validate(&self, code: &str) -> Result<LuhnCode, LuhnError>
+    {
+        let code_ch = normalise(code, self.separators(), self.min_len(),
+            |ch, index, len| self.accepts(ch, index, len))?;
+
+        if !self.accepts_len(code_ch.len())
+        {
+            return Err(LuhnError::InvalidLength { len: code_ch.len() });
+        }
+
+        let remainder = self.remainder(&code_ch);
+        if remainder != self.expected()
+        {
+            return Err(LuhnError::ChecksumMismatch { expected: self.expected(), actual: remainder });
+        }
+
+        Ok(LuhnCode { digits: code_ch.iter().collect() })
+    }
+
+    fn is_valid(&self, code: &str) -> bool
+    {
+        self.validate(code).is_ok()
+    }
+}
+
+
+/// Luhn mod 10, used by credit cards and the Canadian SIN.
+pub struct Luhn;
+
+/// Verhoeff dihedral-group checksum.
+pub struct Verhoeff;
+
+/// Damm quasigroup checksum.
+pub struct Damm;
+
+/// ISO 7064 mod 97-10 over digits and letters (`A` = 10, ..., `Z` = 35).
+pub struct Mod97;
+
+/// IBAN: ISO 7064 mod 97-10 after moving the country code and check digits to the end.
+pub struct Iban;
+
+/// ISBN-10 (weights 10 to 1, mod 11, `X` = 10) and ISBN-13 (weights 1 and 3, mod 10).
+pub struct Isbn;
+
+
+/// Every available algorithm.
+pub fn all() -> Vec<Box<dyn ChecksumAlgorithm>>
+{
+    vec![Box::new(Luhn), Box::new(Verhoeff), Box::new(Damm), Box::new(Mod97), Box::new(Iban), Box::new(Isbn)]
+}
+
+
+/// Look up an algorithm by its [`ChecksumAlgorithm::name`].
+pub fn by_name(name: &str) -> Option<Box<dyn ChecksumAlgorithm>>
+{
+    all().into_iter().find(|a| a.name().eq_ignore_ascii_case(name))
+}
+
+
+/// Remove the `separators` from `code`, upper-case it and check every remaining
+/// character with `accepts(ch, index, len)`.
+/// Codes shorter than `min_len` are rejected before looking at the characters.
+pub(crate) fn normalise<F>(code: &str, separators: &[char], min_len: usize, accepts: F)
+    -> Result<Vec<char>, LuhnError>
+where
+    F: Fn(char, usize, usize) -> bool,
+{
+    let code_ch: Vec<(usize, char)> = code.chars()
+        .enumerate()
+        .filter(|(_, c)| !separators.contains(c))
+        .collect();
+
+    if code_ch.len() < min_len
+    {
+        return Err(LuhnError::TooShort);
+    }
+
+    let len = code_ch.len();
+    code_ch.iter()
+        .enumerate()
+        .map(|(index, (position, ch))|
+        {
+            let upper = ch.to_ascii_uppercase();
+            if accepts(upper, index, len)
+            {
+                Ok(upper)
+            }
+            else
+            {
+                Err(LuhnError::InvalidCharacter { ch: *ch, position: *position })
+            }
+        })
+        .collect()
+}
+
+
+fn to_digits(code: &[char]) -> Vec<u32>
+{
+    code.iter().map(|ch| ch.to_digit(10).unwrap()).collect()
+}
+
+
+impl ChecksumAlgorithm for Luhn
+{
+    fn name(&self) -> &'static str
+    {
+        "luhn"
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        luhn_sum(&to_digits(code)) % 10
+    }
+}
+
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+
+impl ChecksumAlgorithm for Verhoeff
+{
+    fn name(&self) -> &'static str
+    {
+        "verhoeff"
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        to_digits(code).iter()
+            .rev()
+            .enumerate()
+            .fold(0, |c, (i, d)| VERHOEFF_D[c as usize][VERHOEFF_P[i % 8][*d as usize] as usize] as u32)
+    }
+}
+
+
+const DAMM: [[u8; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+
+impl ChecksumAlgorithm for Damm
+{
+    fn name(&self) -> &'static str
+    {
+        "damm"
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        to_digits(code).iter()
+            .fold(0, |interim, d| DAMM[interim as usize][*d as usize] as u32)
+    }
+}
+
+
+/// Remainder modulo 97 of the number obtained by replacing each letter with its two-digit value.
+fn mod97<'a>(code: impl Iterator<Item = &'a char>) -> u32
+{
+    code.map(|ch| ch.to_digit(36).unwrap())
+        .fold(0, |rem, v| if v >= 10 { (rem * 100 + v) % 97 } else { (rem * 10 + v) % 97 })
+}
+
+
+impl ChecksumAlgorithm for Mod97
+{
+    fn name(&self) -> &'static str
+    {
+        "mod97"
+    }
+
+    fn expected(&self) -> u32
+    {
+        1
+    }
+
+    fn accepts(&self, ch: char, _index: usize, _len: usize) -> bool
+    {
+        ch.is_ascii_alphanumeric()
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        mod97(code.iter())
+    }
+}
+
+
+impl ChecksumAlgorithm for Iban
+{
+    fn name(&self) -> &'static str
+    {
+        "iban"
+    }
+
+    fn expected(&self) -> u32
+    {
+        1
+    }
+
+    fn min_len(&self) -> usize
+    {
+        5
+    }
+
+    fn accepts_len(&self, len: usize) -> bool
+    {
+        len <= 34
+    }
+
+    fn accepts(&self, ch: char, index: usize, _len: usize) -> bool
+    {
+        match index
+        {
+            0 | 1 => ch.is_ascii_uppercase(),
+            2 | 3 => ch.is_ascii_digit(),
+            _ => ch.is_ascii_alphanumeric(),
+        }
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        mod97(code[4..].iter().chain(code[..4].iter()))
+    }
+}
+
+
+impl ChecksumAlgorithm for Isbn
+{
+    fn name(&self) -> &'static str
+    {
+        "isbn"
+    }
+
+    fn separators(&self) -> &'static [char]
+    {
+        &[' ', '-']
+    }
+
+    fn accepts_len(&self, len: usize) -> bool
+    {
+        len == 10 || len == 13
+    }
+
+    fn accepts(&self, ch: char, index: usize, len: usize) -> bool
+    {
+        ch.is_ascii_digit() || (ch == 'X' && len == 10 && index == 9)
+    }
+
+    fn remainder(&self, code: &[char]) -> u32
+    {
+        let values = code.iter().map(|ch| if *ch == 'X' { 10 } else { ch.to_digit(10).unwrap() });
+        if code.len() == 10
+        {
+            values.zip((1..=10).rev()).map(|(v, w)| v * w).sum::<u32>() % 11
+        }
+        else
+        {
+            values.zip([1, 3].iter().cycle()).map(|(v, w)| v * w).sum::<u32>() % 10
+        }
+    }
+}
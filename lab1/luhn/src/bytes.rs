@@ -0,0 +1,99 @@
+//! Single-pass Luhn check over the bytes of the code, without allocating.
+//! Available without the `std` feature.
+
+use crate::{double, LuhnError};
+
+
+/// Result of scanning a code once from left to right.
+struct Scan
+{
+    /// Non-space characters seen, counting each UTF-8 sequence once.
+    count: usize,
+    /// `sums[k]` is the sum mod 10 assuming the digits at even offsets from
+    /// the last digit are those whose index has parity `k`.
+    sums: [u32; 2],
+    /// Byte offset of the first character that is neither a digit nor a space.
+    invalid: Option<usize>,
+}
+
+
+impl Scan
+{
+    fn new(code: &[u8]) -> Self
+    {
+        let mut scan = Scan { count: 0, sums: [0, 0], invalid: None };
+
+        for (offset, b) in code.iter().enumerate()
+        {
+            match *b
+            {
+                b' ' => {}
+                b'0'..=b'9' =>
+                {
+                    let d = (*b - b'0') as u32;
+                    let k = scan.count % 2;
+                    scan.sums[k] = add_mod10(scan.sums[k], d);
+                    scan.sums[1 - k] = add_mod10(scan.sums[1 - k], double(d));
+                    scan.count += 1;
+                }
+                // UTF-8 continuation bytes belong to an already counted character
+                0x80..=0xBF =>
+                {
+                    scan.invalid.get_or_insert(offset);
+                }
+                _ =>
+                {
+                    scan.invalid.get_or_insert(offset);
+                    scan.count += 1;
+                }
+            }
+        }
+        scan
+    }
+
+
+    /// Remainder of the Luhn sum; the last digit is never doubled.
+    fn remainder(&self) -> u32
+    {
+        self.sums[(self.count - 1) % 2]
+    }
+}
+
+
+pub(crate) fn add_mod10(sum: u32, digit: u32) -> u32
+{
+    let s = sum + digit;
+    if s >= 10 { s - 10 } else { s }
+}
+
+
+/// Check a Luhn checksum, reporting the same errors as [`crate::validate`].
+pub fn check(code: &str) -> Result<(), LuhnError>
+{
+    let scan = Scan::new(code.as_bytes());
+
+    if scan.count <= 1
+    {
+        return Err(LuhnError::TooShort);
+    }
+    if let Some(offset) = scan.invalid
+    {
+        let ch = code[offset..].chars().next().unwrap_or(char::REPLACEMENT_CHARACTER);
+        return Err(LuhnError::InvalidCharacter { ch, position: code[..offset].chars().count() });
+    }
+
+    match scan.remainder()
+    {
+        0 => Ok(()),
+        actual => Err(LuhnError::ChecksumMismatch { expected: 0, actual }),
+    }
+}
+
+
+/// Check a Luhn checksum over raw bytes, e.g. straight from a keypad or serial buffer.
+/// Any byte other than an ASCII digit or a space makes the code invalid.
+pub fn is_valid(code: &[u8]) -> bool
+{
+    let scan = Scan::new(code);
+    scan.count > 1 && scan.invalid.is_none() && scan.remainder() == 0
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+use crate::{validate, LuhnError};
+
+
+/// Card network, detected from the IIN (the leading digits of the number).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issuer
+{
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    DinersClub,
+    Jcb,
+    UnionPay,
+    Maestro,
+}
+
+
+/// Why a number is not a plausible card number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardError
+{
+    /// The number does not pass the Luhn check.
+    Checksum(LuhnError),
+    /// No known network uses the leading digits.
+    UnknownIssuer,
+    /// The network does not issue numbers of `len` digits.
+    InvalidLength { issuer: Issuer, len: usize },
+}
+
+
+/// A Luhn-valid number whose length matches its issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card
+{
+    number: String,
+    issuer: Issuer,
+}
+
+
+/// IIN ranges as `(first, last, prefix length, issuer)`.
+/// The first matching range wins, so narrower ranges come first.
+const IIN_RANGES: &[(u32, u32, usize, Issuer)] = &[
+    (34, 34, 2, Issuer::Amex),
+    (37, 37, 2, Issuer::Amex),
+    (622126, 622925, 6, Issuer::Discover),
+    (6011, 6011, 4, Issuer::Discover),
+    (644, 649, 3, Issuer::Discover),
+    (65, 65, 2, Issuer::Discover),
+    (5018, 5018, 4, Issuer::Maestro),
+    (5020, 5020, 4, Issuer::Maestro),
+    (5038, 5038, 4, Issuer::Maestro),
+    (5893, 5893, 4, Issuer::Maestro),
+    (6304, 6304, 4, Issuer::Maestro),
+    (6759, 6759, 4, Issuer::Maestro),
+    (6761, 6763, 4, Issuer::Maestro),
+    (3095, 3095, 4, Issuer::DinersClub),
+    (300, 305, 3, Issuer::DinersClub),
+    (36, 36, 2, Issuer::DinersClub),
+    (38, 39, 2, Issuer::DinersClub),
+    (3528, 3589, 4, Issuer::Jcb),
+    (2221, 2720, 4, Issuer::Mastercard),
+    (51, 55, 2, Issuer::Mastercard),
+    (62, 62, 2, Issuer::UnionPay),
+    (4, 4, 1, Issuer::Visa),
+];
+
+
+impl Issuer
+{
+    pub fn name(&self) -> &'static str
+    {
+        match self
+        {
+            Issuer::Visa => "Visa",
+            Issuer::Mastercard => "Mastercard",
+            Issuer::Amex => "American Express",
+            Issuer::Discover => "Discover",
+            Issuer::DinersClub => "Diners Club",
+            Issuer::Jcb => "JCB",
+            Issuer::UnionPay => "UnionPay",
+            Issuer::Maestro => "Maestro",
+        }
+    }
+
+
+    /// Number lengths issued by the network.
+    pub fn lengths(&self) -> &'static [usize]
+    {
+        match self
+        {
+            Issuer::Visa => &[13, 16, 19],
+            Issuer::Mastercard => &[16],
+            Issuer::Amex => &[15],
+            Issuer::Discover | Issuer::Jcb | Issuer::UnionPay => &[16, 17, 18, 19],
+            Issuer::DinersClub => &[14, 15, 16, 17, 18, 19],
+            Issuer::Maestro => &[12, 13, 14, 15, 16, 17, 18, 19],
+        }
+    }
+
+
+    /// Size of the digit groups in the display format of a `len` digit number.
+    fn groups(&self, len: usize) -> Vec<usize>
+    {
+        match (self, len)
+        {
+            (Issuer::Amex, 15) => vec![4, 6, 5],
+            (Issuer::DinersClub, 14) => vec![4, 6, 4],
+            _ =>
+            {
+                let mut groups = vec![4; len / 4];
+                if len % 4 != 0
+                {
+                    groups.push(len % 4);
+                }
+                groups
+            }
+        }
+    }
+}
+
+
+impl fmt::Display for Issuer
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.name())
+    }
+}
+
+
+impl fmt::Display for CardError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            CardError::Checksum(e) => write!(f, "{}", e),
+            CardError::UnknownIssuer => write!(f, "unknown card issuer"),
+            CardError::InvalidLength { issuer, len } =>
+                write!(f, "{} does not issue {} digit numbers", issuer, len),
+        }
+    }
+}
+
+
+impl std::error::Error for CardError {}
+
+
+/// Detect the issuer from the leading digits of `number`, spaces ignored.
+/// The number is not required to be complete or valid.
+pub fn detect_issuer(number: &str) -> Option<Issuer>
+{
+    let digits: String = number.chars().filter(|c| *c != ' ').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    IIN_RANGES.iter()
+        .find(|(first, last, len, _)|
+            digits.get(..*len)
+                .and_then(|prefix| prefix.parse::<u32>().ok())
+                .is_some_and(|prefix| *first <= prefix && prefix <= *last))
+        .map(|(_, _, _, issuer)| *issuer)
+}
+
+
+impl Card
+{
+    /// Check that `number` passes [`validate`] and has a length its issuer uses.
+    pub fn parse(number: &str) -> Result<Card, CardError>
+    {
+        let code = validate(number).map_err(CardError::Checksum)?;
+        let issuer = detect_issuer(code.digits()).ok_or(CardError::UnknownIssuer)?;
+        let len = code.digits().len();
+
+        if !issuer.lengths().contains(&len)
+        {
+            return Err(CardError::InvalidLength { issuer, len });
+        }
+        Ok(Card { number: code.digits().to_string(), issuer })
+    }
+
+
+    pub fn issuer(&self) -> Issuer
+    {
+        self.issuer
+    }
+
+
+    /// The digits of the number, without spaces.
+    pub fn number(&self) -> &str
+    {
+        &self.number
+    }
+
+
+    /// The number in its canonical grouping, e.g. `4111 1111 1111 1111` or `3782 822463 10005`.
+    pub fn formatted(&self) -> String
+    {
+        self.group(&self.number)
+    }
+
+
+    /// The formatted number with every digit but the last four replaced by `*`.
+    pub fn masked(&self) -> String
+    {
+        let hidden = self.number.len().saturating_sub(4);
+        let masked: String = self.number.chars()
+            .enumerate()
+            .map(|(i, c)| if i < hidden { '*' } else { c })
+            .collect();
+        self.group(&masked)
+    }
+
+
+    fn group(&self, number: &str) -> String
+    {
+        let mut groups: Vec<&str> = Vec::new();
+        let mut start = 0;
+        for size in self.issuer.groups(number.len())
+        {
+            groups.push(&number[start..start + size]);
+            start += size;
+        }
+        groups.join(" ")
+    }
+}
+
+
+impl fmt::Display for Card
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}", self.formatted())
+    }
+}
@@ -1,52 +1,199 @@
-/// Check a Luhn checksum.
-pub fn is_valid(code: &str) -> bool 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod accumulator;
+#[cfg(feature = "std")]
+pub mod algorithms;
+pub mod bytes;
+#[cfg(feature = "std")]
+pub mod card;
+
+use core::fmt;
+
+#[cfg(feature = "std")]
+use algorithms::normalise;
+
+
+/// Placeholder for the single unknown digit accepted by [`fill_unknown`].
+#[cfg(feature = "std")]
+pub const UNKNOWN_DIGIT: char = '?';
+
+/// A code that passed a checksum, stored without its separators.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LuhnCode
 {
-    if code.len() <= 1
+    digits: String,
+}
+
+
+/// Why a code failed a checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LuhnError
+{
+    /// Too few characters once separators are removed.
+    TooShort,
+    /// A character that is neither accepted by the algorithm nor a separator;
+    /// `position` is the index of the character in the original input.
+    InvalidCharacter { ch: char, position: usize },
+    /// The algorithm does not accept codes of `len` characters.
+    InvalidLength { len: usize },
+    /// The weighted sum does not end in `expected`.
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// [`fill_unknown`] needs exactly one unknown digit, `count` were found.
+    UnknownDigits { count: usize },
+}
+
+
+#[cfg(feature = "std")]
+impl LuhnCode
+{
+    /// The digits of the code, separators removed.
+    pub fn digits(&self) -> &str
+    {
+        &self.digits
+    }
+
+
+    /// The trailing check digit; the ISBN-10 `X` counts as 10.
+    pub fn check_digit(&self) -> u32
     {
-        println!("Code is too short!");
-        return false;
+        match self.digits.chars().last()
+        {
+            Some('X') => 10,
+            Some(ch) => ch.to_digit(10).unwrap_or(0),
+            None => 0,
+        }
     }
-    else 
+}
+
+
+#[cfg(feature = "std")]
+impl fmt::Display for LuhnCode
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
     {
-        let mut code_ch: Vec<char> = 
-            code.chars()
-                .filter(|c| *c != ' ')
-                .collect();
+        write!(f, "{}", self.digits)
+    }
+}
 
-        if code_ch.len() <= 1
+
+impl fmt::Display for LuhnError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
         {
-            println!("Code is too short!");
-            return false;
+            LuhnError::TooShort => write!(f, "code is too short"),
+            LuhnError::InvalidLength { len } => write!(f, "invalid code length {}", len),
+            LuhnError::InvalidCharacter { ch, position } =>
+                write!(f, "invalid character {:?} at position {}", ch, position),
+            LuhnError::ChecksumMismatch { expected, actual } =>
+                write!(f, "checksum remainder is {}, expected {}", actual, expected),
+            LuhnError::UnknownDigits { count } =>
+                write!(f, "expected exactly one unknown digit, found {}", count),
         }
-        else
+    }
+}
+
+
+#[cfg(feature = "std")]
+impl std::error::Error for LuhnError {}
+
+
+/// Check a Luhn checksum, reporting why the code is invalid.
+#[cfg(feature = "std")]
+pub fn validate(code: &str) -> Result<LuhnCode, LuhnError>
+{
+    bytes::check(code)?;
+    Ok(LuhnCode { digits: code.chars().filter(|c| *c != ' ').collect() })
+}
+
+
+/// Check a Luhn checksum.
+pub fn is_valid(code: &str) -> bool
+{
+    bytes::check(code).is_ok()
+}
+
+
+/// Compute the check digit to append to `payload`.
+#[cfg(feature = "std")]
+pub fn check_digit(payload: &str) -> Result<u32, LuhnError>
+{
+    let mut digits = parse_digits(payload, 1)?;
+    digits.push(0);
+    Ok((10 - luhn_sum(&digits) % 10) % 10)
+}
+
+
+/// Append the check digit to `payload`, returning the complete code.
+#[cfg(feature = "std")]
+pub fn complete(payload: &str) -> Result<LuhnCode, LuhnError>
+{
+    let check = check_digit(payload)?;
+    let mut digits = parse_digits(payload, 1)?;
+    digits.push(check);
+    Ok(LuhnCode { digits: to_string(&digits) })
+}
+
+
+/// Recover a code with exactly one digit replaced by [`UNKNOWN_DIGIT`].
+///
+/// Every candidate is tried; since the Luhn check detects any single-digit
+/// error, at most one of them can be valid.
+#[cfg(feature = "std")]
+pub fn fill_unknown(code: &str) -> Result<LuhnCode, LuhnError>
+{
+    let count = code.chars().filter(|c| *c == UNKNOWN_DIGIT).count();
+    if count != 1
+    {
+        return Err(LuhnError::UnknownDigits { count });
+    }
+
+    let mut result = Err(LuhnError::UnknownDigits { count });
+    for d in 0..10
+    {
+        result = validate(&code.replacen(UNKNOWN_DIGIT, &d.to_string(), 1));
+        match result
         {
-            match code_ch.iter().find(|c| !(*c).is_numeric())
-            {
-                None => {},
-                Some(_) => 
-                {
-                    println!("Invalid format");
-                    return false;
-                },
-            }
-            let mut code_int: Vec<u32> = code_ch.iter()
-                .map(|c| c.to_digit(10).unwrap())
-                .rev()
-                .collect();
-
-            let mut sum: u32 = 0;
-            let mut rep: u32;
-            for i in (0..code_int.len() - 1).step_by(2)
-            {
-                rep = 2 * code_int[i+1];
-                if rep > 9
-                {
-                    rep -= 9;
-                }
-                sum = sum + code_int[i] + rep;
-            }
-
-            if sum % 10 == 0 {true} else {false}
-        }  
+            Err(LuhnError::ChecksumMismatch { .. }) => continue,
+            _ => break,
+        }
     }
+    result
+}
+
+
+/// Strip the spaces from `code` and convert the remaining characters to digits.
+#[cfg(feature = "std")]
+fn parse_digits(code: &str, min_len: usize) -> Result<Vec<u32>, LuhnError>
+{
+    let code_ch = normalise(code, &[' '], min_len, |ch, _, _| ch.is_ascii_digit())?;
+    Ok(code_ch.iter().map(|ch| ch.to_digit(10).unwrap()).collect())
+}
+
+
+/// Sum of the digits, doubling every second one starting from the rightmost.
+#[cfg(feature = "std")]
+pub(crate) fn luhn_sum(digits: &[u32]) -> u32
+{
+    digits.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 1 { double(*d) } else { *d })
+        .sum()
+}
+
+
+#[cfg(feature = "std")]
+fn to_string(digits: &[u32]) -> String
+{
+    digits.iter().map(|d| char::from_digit(*d, 10).unwrap()).collect()
+}
+
+
+pub(crate) fn double(digit: u32) -> u32
+{
+    let rep = 2 * digit;
+    if rep > 9 { rep - 9 } else { rep }
 }
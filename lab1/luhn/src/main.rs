@@ -1,20 +1,169 @@
-use core::panic;
-use std::{env::args, process::exit};
+use std::fs::File;
+use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, Write};
+use std::process::exit;
+
+use clap::{Parser, ValueEnum};
+use serde_json::json;
+
+use luhn::algorithms::{all, by_name, ChecksumAlgorithm};
+
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format
+{
+    Text,
+    Csv,
+    Json,
+}
+
+
+/// Validate codes given as arguments, read from files or from stdin, one per line.
+/// Exits with 1 if any code is invalid.
+#[derive(Parser, Debug)]
+struct Arguments
+{
+    /// Codes to check; stdin is read when neither codes nor files are given
+    codes: Vec<String>,
+
+    /// Read codes from a file, one per line (`-` for stdin)
+    #[arg(short, long = "file")]
+    files: Vec<String>,
+
+    /// Checksum algorithm
+    #[arg(short, long, default_value = "luhn")]
+    algorithm: String,
+
+    /// Output format: plain text, CSV or JSON lines
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
 
-use luhn::is_valid;
 
 fn main()
 {
-    let args: Vec<String> = args().skip(1).collect();
-    if args.len() <= 0
+    let args = Arguments::parse();
+
+    let algorithm = match by_name(&args.algorithm)
+    {
+        Some(a) => a,
+        None =>
+        {
+            let names: Vec<&str> = all().iter().map(|a| a.name()).collect();
+            eprintln!("Unknown algorithm {}, expected one of: {}", args.algorithm, names.join(", "));
+            exit(2);
+        }
+    };
+
+    let mut out = BufWriter::new(stdout().lock());
+    let mut all_valid = true;
+
+    if let Format::Csv = args.format
+    {
+        write_or_exit(writeln!(out, "code,valid,reason"));
+    }
+
+    for code in args.codes.iter()
+    {
+        all_valid &= check(&*algorithm, code, args.format, &mut out);
+    }
+
+    let mut files = args.files.clone();
+    if args.codes.is_empty() && files.is_empty()
+    {
+        files.push("-".to_string());
+    }
+
+    for file in files.iter()
+    {
+        let reader: Box<dyn BufRead> = if file == "-"
+        {
+            Box::new(stdin().lock())
+        }
+        else
+        {
+            match File::open(file)
+            {
+                Ok(f) => Box::new(BufReader::new(f)),
+                Err(e) =>
+                {
+                    eprintln!("Cannot open {}: {}", file, e);
+                    exit(2);
+                }
+            }
+        };
+
+        for line in reader.lines()
+        {
+            let line = match line
+            {
+                Ok(l) => l,
+                Err(e) =>
+                {
+                    eprintln!("Cannot read {}: {}", file, e);
+                    exit(2);
+                }
+            };
+            let code = line.trim_end_matches('\r');
+            if !code.trim().is_empty()
+            {
+                all_valid &= check(&*algorithm, code, args.format, &mut out);
+            }
+        }
+    }
+
+    write_or_exit(out.flush());
+    exit(if all_valid { 0 } else { 1 });
+}
+
+
+/// Validate `code` and write one result line; returns whether the code is valid.
+fn check(algorithm: &dyn ChecksumAlgorithm, code: &str, format: Format, out: &mut impl Write) -> bool
+{
+    let result = algorithm.validate(code);
+    let reason = result.as_ref().err().map(|e| e.to_string());
+
+    let written = match format
     {
-        println!("Missing arguments");
-        exit(1);
+        Format::Text => match &reason
+        {
+            None => writeln!(out, "{}: valid", code),
+            Some(r) => writeln!(out, "{}: not valid ({})", code, r),
+        },
+        Format::Csv => writeln!(out, "{},{},{}",
+            csv_field(code), result.is_ok(), csv_field(reason.as_deref().unwrap_or(""))),
+        Format::Json => writeln!(out, "{}",
+            json!({ "code": code, "valid": result.is_ok(), "reason": reason })),
+    };
+    write_or_exit(written);
+
+    result.is_ok()
+}
+
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String
+{
+    if field.contains([',', '"', '\n', '\r'])
+    {
+        format!("\"{}\"", field.replace('"', "\"\""))
     }
-    else 
+    else
+    {
+        field.to_string()
+    }
+}
+
+
+/// Stop on a write error, quietly when stdout is closed, e.g. when piped
+/// into `head`.
+fn write_or_exit(result: std::io::Result<()>)
+{
+    if let Err(e) = result
     {
-        let valid = is_valid(&args[0]);
-        println!("Code is{} valid", if valid {""} else {" not"});
+        if e.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            eprintln!("Cannot write the output: {}", e);
+        }
+        exit(2);
     }
-    
-}
\ No newline at end of file
+}
@@ -0,0 +1,68 @@
+use luhn::accumulator::LuhnAccumulator;
+use luhn::{check_digit, is_valid, LuhnError};
+
+#[test]
+fn matches_is_valid_at_every_prefix() {
+    let code = "4539 3195 0343 6467";
+    let mut acc = LuhnAccumulator::new();
+    for (i, ch) in code.char_indices() {
+        acc.push(ch).unwrap();
+        assert_eq!(acc.is_valid(), is_valid(&code[..=i]), "{}", &code[..=i]);
+    }
+    assert!(acc.is_valid());
+}
+
+#[test]
+fn completing_digit_matches_check_digit() {
+    let mut acc = LuhnAccumulator::new();
+    let payload = "7992739871";
+    for (i, ch) in payload.char_indices() {
+        acc.push(ch).unwrap();
+        assert_eq!(Ok(acc.completing_digit()), check_digit(&payload[..=i]));
+    }
+    acc.push_digit(acc.completing_digit()).unwrap();
+    assert!(acc.is_valid());
+}
+
+#[test]
+fn chunks_with_spaces() {
+    let mut acc = LuhnAccumulator::new();
+    acc.push_str("055 4").unwrap();
+    acc.push_str("44 28").unwrap();
+    assert_eq!(acc.len(), 8);
+    assert_eq!(acc.completing_digit(), 5);
+    acc.push_str("5").unwrap();
+    assert!(acc.is_valid());
+}
+
+#[test]
+fn reports_remainder_and_errors() {
+    let mut acc = LuhnAccumulator::new();
+    assert!(acc.is_empty());
+    assert_eq!(acc.check(), Err(LuhnError::TooShort));
+    acc.push_str("055 444 286").unwrap();
+    assert_eq!(acc.remainder(), 1);
+    assert_eq!(acc.check(), Err(LuhnError::ChecksumMismatch { expected: 0, actual: 1 }));
+}
+
+#[test]
+fn invalid_characters_are_rejected_without_changing_state() {
+    let mut acc = LuhnAccumulator::new();
+    assert_eq!(
+        acc.push_str("05a9"),
+        Err(LuhnError::InvalidCharacter { ch: 'a', position: 2 })
+    );
+    assert_eq!(acc.len(), 2);
+    acc.push('9').unwrap();
+    assert!(acc.is_valid());
+    assert!(acc.push_digit(10).is_err());
+    assert_eq!(acc.len(), 3);
+}
+
+#[test]
+fn reset_clears_state() {
+    let mut acc = LuhnAccumulator::new();
+    acc.push_str("059").unwrap();
+    acc.reset();
+    assert_eq!(acc, LuhnAccumulator::new());
+}
@@ -0,0 +1,87 @@
+use luhn::algorithms::*;
+use luhn::LuhnError;
+
+#[test]
+fn luhn_matches_is_valid() {
+    assert!(Luhn.is_valid("055 444 285"));
+    assert!(!Luhn.is_valid("055 444 286"));
+}
+
+#[test]
+fn verhoeff() {
+    assert!(Verhoeff.is_valid("2363"));
+    assert!(Verhoeff.is_valid("236 3"));
+    assert_eq!(
+        Verhoeff.validate("2364"),
+        Err(LuhnError::ChecksumMismatch { expected: 0, actual: 1 })
+    );
+}
+
+#[test]
+fn verhoeff_detects_adjacent_transposition() {
+    assert!(!Verhoeff.is_valid("3263"));
+}
+
+#[test]
+fn damm() {
+    assert!(Damm.is_valid("5724"));
+    assert!(!Damm.is_valid("5274"));
+}
+
+#[test]
+fn mod97_numeric() {
+    assert!(Mod97.is_valid("794 44"));
+    assert!(!Mod97.is_valid("794 45"));
+}
+
+#[test]
+fn iban() {
+    assert!(Iban.is_valid("GB82 WEST 1234 5698 7654 32"));
+    assert!(Iban.is_valid("gb82 west 1234 5698 7654 32"));
+    assert!(Iban.is_valid("IT60 X054 2811 1010 0000 0123 456"));
+    assert!(!Iban.is_valid("GB82 WEST 1234 5698 7654 33"));
+}
+
+#[test]
+fn iban_requires_country_code() {
+    assert_eq!(
+        Iban.validate("1282 WEST 1234 5698 7654 32"),
+        Err(LuhnError::InvalidCharacter { ch: '1', position: 0 })
+    );
+}
+
+#[test]
+fn isbn10_with_hyphens_and_x() {
+    assert!(Isbn.is_valid("0-306-40615-2"));
+    assert!(Isbn.is_valid("0-8044-2957-X"));
+    assert_eq!(Isbn.validate("0-8044-2957-X").unwrap().check_digit(), 10);
+    assert!(!Isbn.is_valid("0-306-40615-3"));
+}
+
+#[test]
+fn isbn13() {
+    assert!(Isbn.is_valid("978-0-306-40615-7"));
+    assert!(!Isbn.is_valid("978-0-306-40615-8"));
+}
+
+#[test]
+fn isbn_rejects_other_lengths() {
+    assert_eq!(Isbn.validate("0-306-4061"), Err(LuhnError::InvalidLength { len: 8 }));
+}
+
+#[test]
+fn x_is_only_valid_as_isbn10_check_digit() {
+    assert_eq!(
+        Isbn.validate("978-0-306-4061X-7"),
+        Err(LuhnError::InvalidCharacter { ch: 'X', position: 14 })
+    );
+}
+
+#[test]
+fn algorithms_are_found_by_name() {
+    for algorithm in all() {
+        assert_eq!(by_name(algorithm.name()).unwrap().name(), algorithm.name());
+    }
+    assert!(by_name("IBAN").is_some());
+    assert!(by_name("crc32").is_none());
+}
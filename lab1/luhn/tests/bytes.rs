@@ -0,0 +1,34 @@
+use luhn::algorithms::{ChecksumAlgorithm, Luhn};
+use luhn::bytes;
+
+const CASES: &[&str] = &[
+    "1", "0", " 0", "059", "59", "055 444 285", "055 444 286", "8273 1232 7352 0569",
+    "095 245 88", "055a 444 285", "055-444-285", "055£ 444$ 285", "0000 0", "091", ":9",
+    "059a", "1249①", "234 567 891 234", "59%59", "a", "é", "79927398713", "89927398713",
+];
+
+#[test]
+fn check_reports_the_same_errors_as_the_generic_algorithm() {
+    for code in CASES {
+        assert_eq!(bytes::check(code), Luhn.validate(code).map(|_| ()), "{}", code);
+    }
+}
+
+#[test]
+fn is_valid_matches_check() {
+    for code in CASES {
+        assert_eq!(bytes::is_valid(code.as_bytes()), bytes::check(code).is_ok(), "{}", code);
+    }
+}
+
+#[test]
+fn stray_continuation_bytes_are_invalid() {
+    assert!(!bytes::is_valid(b"05\x809"));
+    assert!(!bytes::is_valid(b"\xff059"));
+}
+
+#[test]
+fn long_codes_do_not_overflow() {
+    let code = "0".repeat(10_000_000);
+    assert!(bytes::is_valid(code.as_bytes()));
+}
@@ -0,0 +1,69 @@
+use luhn::card::*;
+use luhn::{complete, LuhnError};
+
+fn issuer_of(number: &str) -> Issuer {
+    Card::parse(number).unwrap().issuer()
+}
+
+#[test]
+fn detects_issuers() {
+    assert_eq!(issuer_of("4111 1111 1111 1111"), Issuer::Visa);
+    assert_eq!(issuer_of("5555 5555 5555 4444"), Issuer::Mastercard);
+    assert_eq!(issuer_of("2221 0000 0000 0009"), Issuer::Mastercard);
+    assert_eq!(issuer_of("3782 822463 10005"), Issuer::Amex);
+    assert_eq!(issuer_of("6011 1111 1111 1117"), Issuer::Discover);
+    assert_eq!(issuer_of("3056 930902 5904"), Issuer::DinersClub);
+    assert_eq!(issuer_of("3530 1113 3330 0000"), Issuer::Jcb);
+    assert_eq!(issuer_of("6200 0000 0000 0005"), Issuer::UnionPay);
+    assert_eq!(issuer_of("6759 6498 2643 8453"), Issuer::Maestro);
+}
+
+#[test]
+fn narrower_ranges_take_precedence() {
+    assert_eq!(detect_issuer("622126"), Some(Issuer::Discover));
+    assert_eq!(detect_issuer("622926"), Some(Issuer::UnionPay));
+}
+
+#[test]
+fn partial_numbers_can_be_detected() {
+    assert_eq!(detect_issuer("4"), Some(Issuer::Visa));
+    assert_eq!(detect_issuer("37"), Some(Issuer::Amex));
+    assert_eq!(detect_issuer("3"), None);
+    assert_eq!(detect_issuer("4a"), None);
+}
+
+#[test]
+fn rejects_unknown_issuer() {
+    assert_eq!(Card::parse("1234 5678 9012 3452"), Err(CardError::UnknownIssuer));
+}
+
+#[test]
+fn rejects_invalid_checksum() {
+    assert_eq!(
+        Card::parse("4111 1111 1111 1112"),
+        Err(CardError::Checksum(LuhnError::ChecksumMismatch { expected: 0, actual: 1 }))
+    );
+}
+
+#[test]
+fn rejects_length_not_used_by_issuer() {
+    let number = complete("4111 1111 1111 1111").unwrap();
+    assert_eq!(
+        Card::parse(number.digits()),
+        Err(CardError::InvalidLength { issuer: Issuer::Visa, len: 17 })
+    );
+}
+
+#[test]
+fn formats_in_canonical_groups() {
+    assert_eq!(Card::parse("4111111111111111").unwrap().formatted(), "4111 1111 1111 1111");
+    assert_eq!(Card::parse("378282246310005").unwrap().formatted(), "3782 822463 10005");
+    assert_eq!(Card::parse("30569309025904").unwrap().formatted(), "3056 930902 5904");
+    assert_eq!(Card::parse("4222222222222").unwrap().formatted(), "4222 2222 2222 2");
+}
+
+#[test]
+fn masks_all_but_last_four_digits() {
+    assert_eq!(Card::parse("4111111111111111").unwrap().masked(), "**** **** **** 1111");
+    assert_eq!(Card::parse("378282246310005").unwrap().masked(), "**** ****** *0005");
+}
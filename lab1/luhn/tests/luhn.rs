@@ -119,3 +119,75 @@ fn invalid_char_in_middle_with_sum_divisible_by_10_isnt_allowed() {
 fn valid_strings_with_numeric_unicode_characters_become_invalid() {
     process_valid_case("1249①", false);
 }
+
+#[test]
+fn validate_reports_too_short() {
+    assert_eq!(validate(" 0"), Err(LuhnError::TooShort));
+}
+
+#[test]
+fn validate_reports_invalid_character_position() {
+    assert_eq!(
+        validate("055 4a4 285"),
+        Err(LuhnError::InvalidCharacter { ch: 'a', position: 5 })
+    );
+}
+
+#[test]
+fn validate_reports_checksum_mismatch() {
+    assert_eq!(
+        validate("055 444 286"),
+        Err(LuhnError::ChecksumMismatch { expected: 0, actual: 1 })
+    );
+}
+
+#[test]
+fn validate_returns_code_without_spaces() {
+    let code = validate("055 444 285").unwrap();
+    assert_eq!(code.digits(), "055444285");
+    assert_eq!(code.check_digit(), 5);
+}
+
+#[test]
+fn odd_length_codes_count_the_leading_digit() {
+    process_valid_case("79927398713", true);
+    process_valid_case("89927398713", false);
+}
+
+#[test]
+fn check_digit_of_a_payload() {
+    assert_eq!(check_digit("7992739871"), Ok(3));
+    assert_eq!(check_digit("055 444 28"), Ok(5));
+}
+
+#[test]
+fn check_digit_of_an_empty_payload_is_too_short() {
+    assert_eq!(check_digit("  "), Err(LuhnError::TooShort));
+}
+
+#[test]
+fn complete_appends_the_check_digit() {
+    let code = complete("7992 7398 71").unwrap();
+    assert_eq!(code.digits(), "79927398713");
+    assert!(is_valid(code.digits()));
+}
+
+#[test]
+fn fill_unknown_recovers_the_missing_digit() {
+    assert_eq!(fill_unknown("055 4?4 285").unwrap().digits(), "055444285");
+    assert_eq!(fill_unknown("7992739871?").unwrap().digits(), "79927398713");
+}
+
+#[test]
+fn fill_unknown_needs_exactly_one_placeholder() {
+    assert_eq!(fill_unknown("055 444 285"), Err(LuhnError::UnknownDigits { count: 0 }));
+    assert_eq!(fill_unknown("05? 4?4 285"), Err(LuhnError::UnknownDigits { count: 2 }));
+}
+
+#[test]
+fn fill_unknown_reports_other_invalid_characters() {
+    assert_eq!(
+        fill_unknown("0a5 4?4 285"),
+        Err(LuhnError::InvalidCharacter { ch: 'a', position: 1 })
+    );
+}
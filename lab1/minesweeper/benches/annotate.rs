@@ -0,0 +1,120 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+use minesweeper::topology::{count_mines, Rectangular};
+use minesweeper::{annotate, bytes};
+
+const ROWS: usize = 1000;
+const COLS: usize = 1000;
+
+
+/// The original `Vec<char>` annotation, up to three passes per row, kept as a baseline.
+fn legacy_annotate(minefield: &[&str]) -> Vec<String>
+{
+    let mut new_mf: Vec<String> = Vec::new();
+    for i in 0..minefield.len()
+    {
+        let mut ann_line = lookup_same_line(&minefield[i].chars().collect::<Vec<_>>());
+        if i > 0
+        {
+            ann_line = lookup_above_bottom(&ann_line, &minefield[i-1].chars().collect::<Vec<_>>());
+        }
+        if i < minefield.len() - 1
+        {
+            ann_line = lookup_above_bottom(&ann_line, &minefield[i+1].chars().collect::<Vec<_>>());
+        }
+        new_mf.push(ann_line.iter().collect());
+    }
+    new_mf
+}
+
+
+fn lookup_same_line(line: &[char]) -> Vec<char>
+{
+    let mut ann_line: Vec<char> = Vec::new();
+    for i in 0..line.len()
+    {
+        if line[i] == '*'
+        {
+            ann_line.push('*');
+            continue;
+        }
+        let mut count = line[i].to_digit(10).unwrap_or(0);
+        if i > 0 && line[i-1] == '*'
+        {
+            count += 1;
+        }
+        if i < line.len() - 1 && line[i+1] == '*'
+        {
+            count += 1;
+        }
+        ann_line.push(char::from_digit(count, 10).unwrap());
+    }
+    ann_line
+}
+
+
+fn lookup_above_bottom(current: &[char], other: &[char]) -> Vec<char>
+{
+    let mut ann_line: Vec<char> = Vec::new();
+    for i in 0..current.len()
+    {
+        if current[i] == '*'
+        {
+            ann_line.push('*');
+            continue;
+        }
+        let mut count = current[i].to_digit(10).unwrap();
+        if other[i] == '*'
+        {
+            count += 1;
+        }
+        if i > 0 && other[i-1] == '*'
+        {
+            count += 1;
+        }
+        if i < other.len() - 1 && other[i+1] == '*'
+        {
+            count += 1;
+        }
+        ann_line.push(char::from_digit(count, 10).unwrap());
+    }
+    ann_line
+}
+
+
+/// A board with about a fifth of the cells mined.
+fn cells() -> Vec<u8>
+{
+    let mut seed: u64 = 42;
+    (0..ROWS * COLS)
+        .map(|_|
+        {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            if (seed >> 60) < 3 { b'*' } else { b' ' }
+        })
+        .collect()
+}
+
+
+fn bench_annotate(c: &mut Criterion)
+{
+    let cells = cells();
+    let rows: Vec<&str> = cells.chunks(COLS).map(|row| std::str::from_utf8(row).unwrap()).collect();
+    let mut group = c.benchmark_group("annotate");
+    group.throughput(Throughput::Elements((ROWS * COLS) as u64));
+    group.sample_size(10);
+
+    group.bench_function("legacy_vec", |b| b.iter(|| legacy_annotate(black_box(&rows))));
+    // The neighbours of each cell, as on the other topologies
+    let mines: Vec<bool> = cells.iter().map(|b| *b == b'*').collect();
+    group.bench_function("neighbours", |b| b.iter(|| count_mines(&Rectangular::new(ROWS, COLS), black_box(&mines))));
+    group.bench_function("annotate", |b| b.iter(|| annotate(black_box(&rows))));
+    group.bench_function("bytes", |b| b.iter(|| bytes::annotate(black_box(&cells), COLS).unwrap()));
+    #[cfg(feature = "rayon")]
+    group.bench_function("par_bytes", |b| b.iter(|| bytes::par_annotate(black_box(&cells), COLS).unwrap()));
+    group.finish();
+}
+
+
+criterion_group!(benches, bench_annotate);
+criterion_main!(benches);
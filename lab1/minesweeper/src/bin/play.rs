@@ -0,0 +1,490 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, stdout, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::Parser;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use minesweeper::game::{Cell, Difficulty, Game, GameState};
+use minesweeper::generator::{Generator, GeneratorError};
+use minesweeper::replay::{Action, Move, Replay, Stats};
+
+
+/// Play minesweeper in the terminal.
+///
+/// Arrows or hjkl move, space or enter reveals (or chords on a number),
+/// f flags, n starts a new game and q quits. With --no-guess the game
+/// starts with the middle of the board open. Games can be recorded to a
+/// replay file and played back with their statistics.
+#[derive(Parser, Debug)]
+struct Arguments
+{
+    /// beginner, intermediate or expert
+    #[arg(short, long, default_value = "beginner")]
+    difficulty: Difficulty,
+
+    /// Custom number of rows, columns and mines, overriding the difficulty
+    #[arg(long, num_args = 3, value_names = ["ROWS", "COLS", "MINES"])]
+    custom: Option<Vec<usize>>,
+
+    /// Seed of the board, random by default
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Only deal boards that can be cleared without guessing, starting from the middle
+    #[arg(long)]
+    no_guess: bool,
+
+    /// Save the moves of the last game to this replay file as they are played
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Play back a replay file instead of playing
+    #[arg(long, conflicts_with_all = ["custom", "seed", "no_guess"])]
+    replay: Option<PathBuf>,
+
+    /// Playback speed, 2 for twice as fast
+    #[arg(long, default_value_t = 1.0, requires = "replay")]
+    speed: f64,
+
+    /// Print the result and the statistics of the replay instead of playing it back
+    #[arg(long, requires = "replay")]
+    stats: bool,
+}
+
+
+/// A replay shown at `speed` times the pace it was played at.
+struct Playback
+{
+    moves: Vec<Move>,
+    next: usize,
+    start: Instant,
+    speed: f64,
+}
+
+
+impl Playback
+{
+    /// Time in the replayed game, stopping at the last move.
+    fn clock(&self) -> Duration
+    {
+        let clock = self.start.elapsed().mul_f64(self.speed);
+        match self.moves.last()
+        {
+            Some(last) if self.next == self.moves.len() => clock.min(last.time),
+            _ => clock,
+        }
+    }
+
+
+    /// The next move, if its time has come.
+    fn due(&mut self) -> Option<Move>
+    {
+        let next = *self.moves.get(self.next)?;
+        if next.time > self.clock()
+        {
+            return None;
+        }
+        self.next += 1;
+        Some(next)
+    }
+
+
+    /// Real time until the next move.
+    fn wait(&self) -> Option<Duration>
+    {
+        self.moves.get(self.next).map(|m| m.time.saturating_sub(self.clock()).div_f64(self.speed))
+    }
+}
+
+
+/// Raw mode and the alternate screen, left when dropped, so that errors and
+/// panics also give the terminal back.
+struct Terminal;
+
+
+impl Terminal
+{
+    fn enter(out: &mut impl Write) -> io::Result<Terminal>
+    {
+        terminal::enable_raw_mode()?;
+        let terminal = Terminal;
+        // Leave before the panic message, or it is printed on the alternate screen
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info|
+        {
+            Terminal::leave();
+            hook(info);
+        }));
+        execute!(out, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(terminal)
+    }
+
+
+    fn leave()
+    {
+        let _ = execute!(stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+
+impl Drop for Terminal
+{
+    fn drop(&mut self)
+    {
+        Terminal::leave();
+    }
+}
+
+
+struct Ui
+{
+    game: Game,
+    row: usize,
+    col: usize,
+    started: Option<Instant>,
+    finished: Option<Duration>,
+    moves: Vec<Move>,
+    record: Option<PathBuf>,
+    playback: Option<Playback>,
+}
+
+
+impl Ui
+{
+    fn new(game: Game, record: Option<PathBuf>) -> Self
+    {
+        let (row, col) = (game.rows() / 2, game.cols() / 2);
+        Ui { game, row, col, started: None, finished: None, moves: Vec::new(), record, playback: None }
+    }
+
+
+    fn elapsed(&self) -> u64
+    {
+        if let Some(playback) = &self.playback
+        {
+            return playback.clock().as_secs();
+        }
+        match (self.started, self.finished)
+        {
+            (_, Some(time)) => time.as_secs(),
+            (Some(start), None) => start.elapsed().as_secs(),
+            (None, None) => 0,
+        }
+    }
+
+
+    fn move_by(&mut self, dr: isize, dc: isize)
+    {
+        self.row = self.row.saturating_add_signed(dr).min(self.game.rows() - 1);
+        self.col = self.col.saturating_add_signed(dc).min(self.game.cols() - 1);
+    }
+
+
+    fn reveal(&mut self) -> io::Result<()>
+    {
+        if self.started.is_none()
+        {
+            self.started = Some(Instant::now());
+        }
+        let action = match self.game.cell(self.row, self.col)
+        {
+            Cell::Revealed => Action::Chord,
+            _ => Action::Reveal,
+        };
+        self.play(action)
+    }
+
+
+    /// Play `action` on the cell under the cursor.
+    fn play(&mut self, action: Action) -> io::Result<()>
+    {
+        let time = self.started.map_or(Duration::ZERO, |start| start.elapsed());
+        self.apply(Move::new(action, self.row, self.col, time))
+    }
+
+
+    fn apply(&mut self, m: Move) -> io::Result<()>
+    {
+        // Revealing a flag or playing after the end does nothing
+        if m.apply(&mut self.game).is_ok()
+        {
+            self.moves.push(m);
+            self.save()?;
+        }
+
+        if self.game.state() != GameState::Playing && self.finished.is_none()
+        {
+            self.finished = self.started.map(|start| start.elapsed());
+        }
+        Ok(())
+    }
+
+
+    /// Rewrite the replay file, once there is a board to save.
+    fn save(&self) -> io::Result<()>
+    {
+        let Some(path) = &self.record
+        else
+        {
+            return Ok(());
+        };
+        match Replay::new(&self.game, self.moves.clone())
+        {
+            Ok(replay) => fs::write(path, replay.to_bytes().map_err(io::Error::other)?),
+            Err(_) => Ok(()),
+        }
+    }
+
+
+    /// Play the moves of the replay whose time has come.
+    fn advance(&mut self) -> io::Result<()>
+    {
+        while let Some(m) = self.playback.as_mut().and_then(|playback| playback.due())
+        {
+            (self.row, self.col) = (m.row, m.col);
+            self.apply(m)?;
+        }
+        Ok(())
+    }
+
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()>
+    {
+        let face = match self.game.state()
+        {
+            GameState::Playing => ":)",
+            GameState::Won => "B)",
+            GameState::Lost => "X(",
+        };
+        queue!(out, MoveTo(0, 0),
+            Print(format!("Mines: {:<4} {}  Time: {:>3}", self.game.mines_left(), face, self.elapsed())),
+            Clear(ClearType::UntilNewLine))?;
+
+        for r in 0..self.game.rows()
+        {
+            queue!(out, MoveTo(0, r as u16 + 2))?;
+            for c in 0..self.game.cols()
+            {
+                let cursor = (r, c) == (self.row, self.col);
+                if cursor
+                {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                self.draw_cell(out, r, c)?;
+                queue!(out, SetAttribute(Attribute::Reset), ResetColor)?;
+                queue!(out, Print(' '))?;
+            }
+        }
+
+        let stats = summary(&Stats::new(&self.game, &self.moves));
+        let status = match (&self.playback, self.game.state())
+        {
+            (Some(playback), _) if playback.next < playback.moves.len() =>
+                format!("Replay: move {} of {}, q quit", playback.next, playback.moves.len()),
+            (Some(_), state) => format!("Replay over, {}. {}. q quit", result(state), stats),
+            (None, GameState::Playing) => "arrows/hjkl move, space reveal, f flag, n new game, q quit".to_string(),
+            (None, GameState::Won) => format!("You won! {}. n new game, q quit", stats),
+            (None, GameState::Lost) => format!("Boom! {}. n new game, q quit", stats),
+        };
+        queue!(out, MoveTo(0, self.game.rows() as u16 + 3), Print(status), Clear(ClearType::UntilNewLine))?;
+        out.flush()
+    }
+
+
+    fn draw_cell(&self, out: &mut impl Write, r: usize, c: usize) -> io::Result<()>
+    {
+        let over = self.game.state() != GameState::Playing;
+        match self.game.cell(r, c)
+        {
+            Cell::Flagged if over && !self.game.is_mine(r, c) =>
+                queue!(out, SetForegroundColor(Color::Red), Print('x')),
+            Cell::Flagged => queue!(out, SetForegroundColor(Color::Red), Print('F')),
+            Cell::Hidden if over && self.game.is_mine(r, c) => queue!(out, Print('*')),
+            Cell::Hidden => queue!(out, SetForegroundColor(Color::DarkGrey), Print('#')),
+            Cell::Revealed if self.game.is_mine(r, c) =>
+                queue!(out, SetBackgroundColor(Color::Red), Print('*')),
+            Cell::Revealed => match self.game.adjacent_mines(r, c)
+            {
+                0 => queue!(out, Print('.')),
+                n => queue!(out, SetForegroundColor(number_color(n)), Print(n)),
+            },
+        }
+    }
+}
+
+
+fn result(state: GameState) -> &'static str
+{
+    match state
+    {
+        GameState::Playing => "unfinished",
+        GameState::Won => "won",
+        GameState::Lost => "lost",
+    }
+}
+
+
+fn summary(stats: &Stats) -> String
+{
+    format!("3BV {}/{}, {} clicks, efficiency {:.0}%, {:.2} 3BV/s",
+        stats.solved_bbbv, stats.bbbv, stats.clicks, stats.efficiency() * 100.0, stats.bbbv_per_second())
+}
+
+
+fn print_stats(replay: &Replay)
+{
+    // The replay was checked when loaded
+    let stats = replay.stats().unwrap();
+    println!("Result: {}", result(stats.state));
+    println!("Time: {:.3} s", stats.time.as_secs_f64());
+    println!("3BV: {}/{}", stats.solved_bbbv, stats.bbbv);
+    println!("Clicks: {}", stats.clicks);
+    println!("Efficiency: {:.0}%", stats.efficiency() * 100.0);
+    println!("3BV/s: {:.2}", stats.bbbv_per_second());
+}
+
+
+fn load(path: &Path) -> Result<Replay, Box<dyn Error>>
+{
+    let replay = Replay::from_bytes(&fs::read(path)?)?;
+    // Catch the moves that cannot be played before showing any
+    replay.play()?;
+    Ok(replay)
+}
+
+
+fn fail(e: impl Display) -> !
+{
+    eprintln!("play: {}", e);
+    std::process::exit(1);
+}
+
+
+/// The classic colours of the numbers.
+fn number_color(n: u8) -> Color
+{
+    match n
+    {
+        1 => Color::Blue,
+        2 => Color::Green,
+        3 => Color::Red,
+        4 => Color::DarkBlue,
+        5 => Color::DarkRed,
+        6 => Color::Cyan,
+        7 => Color::Magenta,
+        _ => Color::Grey,
+    }
+}
+
+
+fn main() -> io::Result<()>
+{
+    let args = Arguments::parse();
+
+    let mut seed = args.seed.unwrap_or_else(|| SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64));
+    let (rows, cols, mines) = match &args.custom
+    {
+        Some(size) => (size[0], size[1], size[2]),
+        None => args.difficulty.size(),
+    };
+    if args.record.is_some() && (rows > 255 || cols > 255)
+    {
+        fail("replays are limited to 255 x 255 boards");
+    }
+    let new_game = |seed: u64| -> Result<Ui, GeneratorError>
+    {
+        if args.no_guess
+        {
+            // Open the board through the UI so that the replay starts from scratch
+            let board = Generator::new(rows, cols, mines)?.seed(seed).generate()?;
+            let minefield: Vec<&str> = board.minefield.iter().map(|row| row.as_str()).collect();
+            let mut ui = Ui::new(Game::from_minefield(&minefield)?, args.record.clone());
+            let (row, col) = board.first_click;
+            ui.apply(Move::new(Action::Reveal, row, col, Duration::ZERO)).unwrap_or_else(|e| fail(e));
+            return Ok(ui);
+        }
+        Ok(Ui::new(Game::new(rows, cols, mines, seed)?, args.record.clone()))
+    };
+
+    let mut ui = match &args.replay
+    {
+        Some(path) =>
+        {
+            let replay = load(path).unwrap_or_else(|e| fail(e));
+            if args.stats
+            {
+                print_stats(&replay);
+                return Ok(());
+            }
+            if args.speed <= 0.0 || !args.speed.is_finite()
+            {
+                fail("the speed must be a positive number");
+            }
+            let mut ui = Ui::new(replay.game().unwrap_or_else(|e| fail(e)), None);
+            ui.playback = Some(Playback { moves: replay.moves, next: 0, start: Instant::now(), speed: args.speed });
+            ui
+        }
+        None => new_game(seed).unwrap_or_else(|e| fail(e)),
+    };
+
+    let mut out = stdout();
+    let _terminal = Terminal::enter(&mut out)?;
+
+    loop
+    {
+        ui.advance()?;
+        ui.draw(&mut out)?;
+        // Wake up every now and then to keep the timer running
+        let timeout = ui.playback.as_ref().and_then(|p| p.wait()).map_or(Duration::from_millis(250), |wait|
+            wait.min(Duration::from_millis(250)));
+        if !event::poll(timeout)?
+        {
+            continue;
+        }
+        let Event::Key(key) = event::read()?
+        else
+        {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press
+        {
+            continue;
+        }
+
+        match key.code
+        {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            // A replay only plays what is in the file
+            _ if ui.playback.is_some() => {}
+            KeyCode::Up | KeyCode::Char('k') => ui.move_by(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => ui.move_by(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => ui.move_by(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => ui.move_by(0, 1),
+            KeyCode::Char(' ') | KeyCode::Enter => ui.reveal()?,
+            KeyCode::Char('f') => ui.play(Action::Flag)?,
+            KeyCode::Char('n') =>
+            {
+                seed = seed.wrapping_add(1);
+                // Keep the current game if no board without guesses turns up
+                if let Ok(game) = new_game(seed)
+                {
+                    ui = game;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
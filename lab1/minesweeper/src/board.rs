@@ -0,0 +1,351 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::topology::Rectangular;
+
+/// The most cells a [`Board`] can have, so that sizes read from a file cannot
+/// exhaust the memory.
+pub const MAX_CELLS: usize = 1 << 24;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardError
+{
+    /// The board has no cells.
+    Empty,
+    /// The row is not as long as the first one.
+    RaggedRow { row: usize, len: usize, expected: usize },
+    InvalidCharacter { row: usize, col: usize, ch: char },
+    /// The board has a different number of cells than its layout.
+    SizeMismatch { cells: usize, expected: usize },
+    /// The MBF data ends before the mine list does.
+    Truncated { len: usize, expected: usize },
+    /// The data goes on after the last mine.
+    TrailingData { len: usize, expected: usize },
+    MineOutOfBounds { row: usize, col: usize },
+    DuplicateMine { row: usize, col: usize },
+    /// The board does not fit the format, such as MBF beyond 255 x 255.
+    TooLarge,
+    /// The board has more than [`MAX_CELLS`] cells.
+    TooManyCells { rows: usize, cols: usize },
+    /// More mines are listed than the board has cells.
+    TooManyMines { mines: usize, cells: usize },
+    /// The JSON could not be read.
+    Json(String),
+}
+
+
+impl fmt::Display for BoardError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            BoardError::Empty => write!(f, "the board has no cells"),
+            BoardError::RaggedRow { row, len, expected } =>
+                write!(f, "row {} has {} cells, expected {}", row + 1, len, expected),
+            BoardError::InvalidCharacter { row, col, ch } =>
+                write!(f, "invalid character {:?} at row {}, column {}", ch, row + 1, col + 1),
+            BoardError::SizeMismatch { cells, expected } =>
+                write!(f, "the board has {} cells, expected {}", cells, expected),
+            BoardError::Truncated { len, expected } =>
+                write!(f, "truncated board: {} bytes, expected {}", len, expected),
+            BoardError::TrailingData { len, expected } =>
+                write!(f, "unexpected data after the board: {} bytes, expected {}", len, expected),
+            BoardError::MineOutOfBounds { row, col } =>
+                write!(f, "mine at row {}, column {} is outside the board", row + 1, col + 1),
+            BoardError::DuplicateMine { row, col } =>
+                write!(f, "mine at row {}, column {} is listed twice", row + 1, col + 1),
+            BoardError::TooLarge => write!(f, "the board is too large for the format"),
+            BoardError::TooManyCells { rows, cols } =>
+                write!(f, "a board of {} x {} cells is over the limit of {} cells", rows, cols, MAX_CELLS),
+            BoardError::TooManyMines { mines, cells } => write!(f, "{} mines do not fit in {} cells", mines, cells),
+            BoardError::Json(e) => write!(f, "invalid JSON board: {}", e),
+        }
+    }
+}
+
+
+impl std::error::Error for BoardError {}
+
+
+/// Formats a [`Board`] can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format
+{
+    /// One line per row, `*` for mines and `.` or a space for the other cells.
+    Text,
+    /// The binary minesweeper board format: width, height, a big-endian
+    /// 16-bit mine count, then column and row of each mine, one byte each.
+    Mbf,
+    /// `{"rows": 2, "cols": 3, "mines": [[0, 1]]}`, mines as `[row, col]`.
+    Json,
+}
+
+
+impl Format
+{
+    /// The format matching the extension of `path`, text by default.
+    pub fn from_path(path: &Path) -> Format
+    {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref()
+        {
+            Some("mbf") => Format::Mbf,
+            Some("json") => Format::Json,
+            _ => Format::Text,
+        }
+    }
+}
+
+
+impl FromStr for Format
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.to_ascii_lowercase().as_str()
+        {
+            "text" | "txt" => Ok(Format::Text),
+            "mbf" => Ok(Format::Mbf),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {}, expected text, mbf or json", s)),
+        }
+    }
+}
+
+
+#[derive(Serialize, Deserialize)]
+struct JsonBoard
+{
+    rows: usize,
+    cols: usize,
+    mines: Vec<[usize; 2]>,
+}
+
+
+/// The position of the mines on a board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board
+{
+    rows: usize,
+    cols: usize,
+    mines: Vec<Vec<bool>>,
+}
+
+
+impl Board
+{
+    /// A board of the given size with mines at the (row, column) positions.
+    pub fn new(rows: usize, cols: usize, mines: &[(usize, usize)]) -> Result<Board, BoardError>
+    {
+        if rows == 0 || cols == 0
+        {
+            return Err(BoardError::Empty);
+        }
+        // Sizes can come from a file: check them before allocating
+        let cells = rows.checked_mul(cols)
+            .filter(|cells| *cells <= MAX_CELLS)
+            .ok_or(BoardError::TooManyCells { rows, cols })?;
+        if mines.len() > cells
+        {
+            return Err(BoardError::TooManyMines { mines: mines.len(), cells });
+        }
+        let mut board = Board { rows, cols, mines: vec![vec![false; cols]; rows] };
+        for &(row, col) in mines
+        {
+            if row >= rows || col >= cols
+            {
+                return Err(BoardError::MineOutOfBounds { row, col });
+            }
+            if board.mines[row][col]
+            {
+                return Err(BoardError::DuplicateMine { row, col });
+            }
+            board.mines[row][col] = true;
+        }
+        Ok(board)
+    }
+
+
+    /// A board from rows in the conventions of [`crate::annotate`], `.` being
+    /// accepted for the free cells as well.
+    pub fn from_rows(rows: &[&str]) -> Result<Board, BoardError>
+    {
+        let cols = rows.first().map_or(0, |row| row.chars().count());
+        if cols == 0
+        {
+            return Err(BoardError::Empty);
+        }
+
+        let mut mines = Vec::with_capacity(rows.len());
+        for (row, line) in rows.iter().enumerate()
+        {
+            let mut cells = Vec::with_capacity(cols);
+            for (col, ch) in line.chars().enumerate()
+            {
+                match ch
+                {
+                    '*' => cells.push(true),
+                    ' ' | '.' => cells.push(false),
+                    _ => return Err(BoardError::InvalidCharacter { row, col, ch }),
+                }
+            }
+            if cells.len() != cols
+            {
+                return Err(BoardError::RaggedRow { row, len: cells.len(), expected: cols });
+            }
+            mines.push(cells);
+        }
+        Ok(Board { rows: rows.len(), cols, mines })
+    }
+
+
+    pub fn parse(data: &[u8], format: Format) -> Result<Board, BoardError>
+    {
+        match format
+        {
+            Format::Text =>
+            {
+                let text = String::from_utf8_lossy(data);
+                Board::from_text(&text)
+            }
+            Format::Mbf => Board::from_mbf(data),
+            Format::Json => Board::from_json(&String::from_utf8_lossy(data)),
+        }
+    }
+
+
+    pub fn serialize(&self, format: Format) -> Result<Vec<u8>, BoardError>
+    {
+        match format
+        {
+            Format::Text => Ok(self.to_text().into_bytes()),
+            Format::Mbf => self.to_mbf(),
+            Format::Json => Ok(self.to_json().into_bytes()),
+        }
+    }
+
+
+    /// One row per line; a final newline and `\r\n` line endings are accepted.
+    pub fn from_text(text: &str) -> Result<Board, BoardError>
+    {
+        let rows: Vec<&str> = text.lines().collect();
+        Board::from_rows(&rows)
+    }
+
+
+    /// One row per line, with `.` for the free cells so that trailing ones
+    /// survive editors.
+    pub fn to_text(&self) -> String
+    {
+        self.mines.iter()
+            .map(|row| row.iter().map(|m| if *m { '*' } else { '.' }).collect::<String>() + "\n")
+            .collect()
+    }
+
+
+    pub fn from_mbf(data: &[u8]) -> Result<Board, BoardError>
+    {
+        if data.len() < 4
+        {
+            return Err(BoardError::Truncated { len: data.len(), expected: 4 });
+        }
+        let (cols, rows) = (data[0] as usize, data[1] as usize);
+        let count = u16::from_be_bytes([data[2], data[3]]) as usize;
+        let expected = 4 + 2 * count;
+        if data.len() < expected
+        {
+            return Err(BoardError::Truncated { len: data.len(), expected });
+        }
+        if data.len() > expected
+        {
+            return Err(BoardError::TrailingData { len: data.len(), expected });
+        }
+
+        let mines: Vec<(usize, usize)> = data[4..].chunks(2).map(|m| (m[1] as usize, m[0] as usize)).collect();
+        Board::new(rows, cols, &mines)
+    }
+
+
+    pub fn to_mbf(&self) -> Result<Vec<u8>, BoardError>
+    {
+        let mines = self.mine_positions();
+        if self.rows > 255 || self.cols > 255 || mines.len() > u16::MAX as usize
+        {
+            return Err(BoardError::TooLarge);
+        }
+
+        let mut data = Vec::with_capacity(4 + 2 * mines.len());
+        data.extend([self.cols as u8, self.rows as u8]);
+        data.extend((mines.len() as u16).to_be_bytes());
+        for (row, col) in mines
+        {
+            data.extend([col as u8, row as u8]);
+        }
+        Ok(data)
+    }
+
+
+    pub fn from_json(json: &str) -> Result<Board, BoardError>
+    {
+        let board: JsonBoard = serde_json::from_str(json).map_err(|e| BoardError::Json(e.to_string()))?;
+        let mines: Vec<(usize, usize)> = board.mines.iter().map(|[row, col]| (*row, *col)).collect();
+        Board::new(board.rows, board.cols, &mines)
+    }
+
+
+    pub fn to_json(&self) -> String
+    {
+        let mines = self.mine_positions().iter().map(|(row, col)| [*row, *col]).collect();
+        serde_json::to_string(&JsonBoard { rows: self.rows, cols: self.cols, mines }).unwrap()
+    }
+
+
+    pub fn rows(&self) -> usize
+    {
+        self.rows
+    }
+
+
+    pub fn cols(&self) -> usize
+    {
+        self.cols
+    }
+
+
+    pub fn is_mine(&self, row: usize, col: usize) -> bool
+    {
+        self.mines[row][col]
+    }
+
+
+    /// Positions of the mines in row-major order.
+    pub fn mine_positions(&self) -> Vec<(usize, usize)>
+    {
+        (0..self.rows)
+            .flat_map(|r| (0..self.cols).map(move |c| (r, c)))
+            .filter(|(r, c)| self.mines[*r][*c])
+            .collect()
+    }
+
+
+    /// The rows in the conventions of [`crate::annotate`]: `*` and spaces.
+    pub fn minefield(&self) -> Vec<String>
+    {
+        self.mines.iter()
+            .map(|row| row.iter().map(|m| if *m { '*' } else { ' ' }).collect())
+            .collect()
+    }
+
+
+    pub fn annotate(&self) -> Vec<String>
+    {
+        let minefield = self.minefield();
+        let rows: Vec<&str> = minefield.iter().map(|row| row.as_str()).collect();
+        crate::annotate_on(&Rectangular::new(self.rows, self.cols), &rows).expect("a board holds only mines and free cells")
+    }
+}
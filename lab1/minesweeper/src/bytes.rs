@@ -0,0 +1,152 @@
+//! Single-pass annotation of rectangular boards held in one flat buffer,
+//! one byte per cell in row-major order: `*` for mines, a space otherwise.
+//!
+//! Each row is read once, into a window of three rows of mine counts, and
+//! each cell is written once. With the `rayon` feature [`par_annotate`]
+//! splits the board into bands of rows annotated in parallel.
+
+use crate::board::BoardError;
+
+
+/// The symbol of each count: a space for none, then digits and, past 9,
+/// letters up to `q` for 26.
+const SYMBOLS: &[u8; 27] = b" 123456789abcdefghijklmnopq";
+
+/// Rows annotated by each task of [`par_annotate`].
+#[cfg(feature = "rayon")]
+const BAND_ROWS: usize = 64;
+
+
+/// The symbol written on a free cell with `count` mines around it.
+pub(crate) fn symbol(count: u32) -> u8
+{
+    *SYMBOLS.get(count as usize).expect("at most 26 mines surround a cell")
+}
+
+
+/// Annotate `cells`, the rows of a board `cols` cells wide one after the
+/// other. The result has the same layout, with counts on the free cells.
+///
+/// A byte other than `*` and a space is reported as the character with its
+/// value.
+pub fn annotate(cells: &[u8], cols: usize) -> Result<Vec<u8>, BoardError>
+{
+    check(cells, cols)?;
+    let mut out = vec![0; cells.len()];
+    if !out.is_empty()
+    {
+        annotate_band(cells, cols, 0, &mut out)?;
+    }
+    Ok(out)
+}
+
+
+/// Like [`annotate`], working on bands of rows in parallel. Errors are the
+/// same as [`annotate`] would report.
+#[cfg(feature = "rayon")]
+pub fn par_annotate(cells: &[u8], cols: usize) -> Result<Vec<u8>, BoardError>
+{
+    use rayon::prelude::*;
+
+    check(cells, cols)?;
+    let mut out = vec![0; cells.len()];
+    if out.is_empty()
+    {
+        return Ok(out);
+    }
+    // Every band reports its first error: the first band failing has the first one
+    let results: Vec<Result<(), BoardError>> = out.par_chunks_mut(BAND_ROWS * cols)
+        .enumerate()
+        .map(|(band, rows)| annotate_band(cells, cols, band * BAND_ROWS, rows))
+        .collect();
+    results.into_iter().collect::<Result<(), _>>()?;
+    Ok(out)
+}
+
+
+/// Annotate `cells` with [`par_annotate`] when available.
+pub(crate) fn annotate_grid(cells: &[u8], cols: usize) -> Result<Vec<u8>, BoardError>
+{
+    #[cfg(feature = "rayon")]
+    return par_annotate(cells, cols);
+    #[cfg(not(feature = "rayon"))]
+    return annotate(cells, cols);
+}
+
+
+fn check(cells: &[u8], cols: usize) -> Result<(), BoardError>
+{
+    let expected = if cols == 0 { 0 } else { cells.len().div_ceil(cols) * cols };
+    if cells.len() != expected
+    {
+        return Err(BoardError::SizeMismatch { cells: cells.len(), expected });
+    }
+    Ok(())
+}
+
+
+/// Annotate the rows from `start` into `out`, which holds as many of them.
+fn annotate_band(cells: &[u8], cols: usize, start: usize, out: &mut [u8]) -> Result<(), BoardError>
+{
+    let rows = cells.len() / cols;
+    let end = start + out.len() / cols;
+
+    // Mines of the rows around the current one, with a free cell on each side
+    let mut above = vec![0u8; cols + 2];
+    let mut here = vec![0u8; cols + 2];
+    let mut below = vec![0u8; cols + 2];
+    if start > 0
+    {
+        load(cells, cols, start - 1, &mut above)?;
+    }
+    load(cells, cols, start, &mut here)?;
+
+    for row in start..end
+    {
+        if row + 1 < rows
+        {
+            load(cells, cols, row + 1, &mut below)?;
+        }
+        else
+        {
+            below.fill(0);
+        }
+
+        let line = &mut out[(row - start) * cols..(row - start + 1) * cols];
+        // Mines in the columns to the left of, at and to the right of each cell
+        let mut left = 0;
+        let mut middle = above[1] + here[1] + below[1];
+        let right = above[2..].iter().zip(&here[2..]).zip(&below[2..]).map(|((a, h), b)| a + h + b);
+        for ((cell, mine), right) in line.iter_mut().zip(&here[1..]).zip(right)
+        {
+            let count = SYMBOLS[(left + middle + right) as usize];
+            *cell = if *mine == 1 { b'*' } else { count };
+            left = middle;
+            middle = right;
+        }
+
+        std::mem::swap(&mut above, &mut here);
+        std::mem::swap(&mut here, &mut below);
+    }
+    Ok(())
+}
+
+
+/// Read the mines of `row` into `mines[1..=cols]` as 0 and 1.
+fn load(cells: &[u8], cols: usize, row: usize, mines: &mut [u8]) -> Result<(), BoardError>
+{
+    let line = &cells[row * cols..(row + 1) * cols];
+    // Without branches on the cells, then look for the culprit only if needed
+    let mut valid = true;
+    for (b, mine) in line.iter().zip(&mut mines[1..])
+    {
+        *mine = (*b == b'*') as u8;
+        valid &= *b == b'*' || *b == b' ';
+    }
+    if valid
+    {
+        return Ok(());
+    }
+    let col = line.iter().position(|b| *b != b'*' && *b != b' ').unwrap();
+    Err(BoardError::InvalidCharacter { row, col, ch: line[col] as char })
+}
@@ -0,0 +1,434 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::annotate;
+use crate::solver::HIDDEN;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell
+{
+    Hidden,
+    Flagged,
+    Revealed,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState
+{
+    Playing,
+    Won,
+    Lost,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError
+{
+    /// The board has no cells.
+    EmptyBoard,
+    /// A row of the minefield is not as long as the first one.
+    RaggedRow { row: usize, len: usize, expected: usize },
+    /// At least one cell must be free of mines.
+    TooManyMines,
+    OutOfBounds,
+    /// The game is already won or lost.
+    GameOver,
+    /// Flagged cells cannot be revealed.
+    Flagged,
+}
+
+
+impl fmt::Display for GameError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            GameError::EmptyBoard => write!(f, "the board has no cells"),
+            GameError::RaggedRow { row, len, expected } =>
+                write!(f, "row {} has {} cells, expected {}", row + 1, len, expected),
+            GameError::TooManyMines => write!(f, "too many mines for the board"),
+            GameError::OutOfBounds => write!(f, "cell out of bounds"),
+            GameError::GameOver => write!(f, "the game is over"),
+            GameError::Flagged => write!(f, "the cell is flagged"),
+        }
+    }
+}
+
+
+impl std::error::Error for GameError {}
+
+
+/// The classic board presets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty
+{
+    /// 9 x 9, 10 mines
+    Beginner,
+    /// 16 x 16, 40 mines
+    Intermediate,
+    /// 16 x 30, 99 mines
+    Expert,
+}
+
+
+impl Difficulty
+{
+    /// Rows, columns and mines of the preset.
+    pub fn size(self) -> (usize, usize, usize)
+    {
+        match self
+        {
+            Difficulty::Beginner => (9, 9, 10),
+            Difficulty::Intermediate => (16, 16, 40),
+            Difficulty::Expert => (16, 30, 99),
+        }
+    }
+}
+
+
+impl FromStr for Difficulty
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.to_ascii_lowercase().as_str()
+        {
+            "beginner" => Ok(Difficulty::Beginner),
+            "intermediate" => Ok(Difficulty::Intermediate),
+            "expert" => Ok(Difficulty::Expert),
+            _ => Err(format!("unknown difficulty {}, expected beginner, intermediate or expert", s)),
+        }
+    }
+}
+
+
+/// A game of minesweeper.
+///
+/// Boards created with [`Game::new`] place their mines on the first reveal,
+/// away from the clicked cell and, when there is room, its neighbours.
+/// Neighbour counts come from [`annotate`].
+#[derive(Debug, Clone)]
+pub struct Game
+{
+    rows: usize,
+    cols: usize,
+    mines: usize,
+    /// Annotated board: `*` for mines, a digit or a space for the others.
+    board: Vec<Vec<char>>,
+    cells: Vec<Vec<Cell>>,
+    state: GameState,
+    revealed: usize,
+    rng: StdRng,
+}
+
+
+impl Game
+{
+    /// A `rows` x `cols` board with `mines` mines, placed on the first reveal.
+    pub fn new(rows: usize, cols: usize, mines: usize, seed: u64) -> Result<Game, GameError>
+    {
+        if rows == 0 || cols == 0
+        {
+            return Err(GameError::EmptyBoard);
+        }
+        if mines >= rows * cols
+        {
+            return Err(GameError::TooManyMines);
+        }
+
+        Ok(Game
+        {
+            rows,
+            cols,
+            mines,
+            board: Vec::new(),
+            cells: vec![vec![Cell::Hidden; cols]; rows],
+            state: GameState::Playing,
+            revealed: 0,
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+
+    pub fn with_difficulty(difficulty: Difficulty, seed: u64) -> Game
+    {
+        let (rows, cols, mines) = difficulty.size();
+        Game::new(rows, cols, mines, seed).unwrap()
+    }
+
+
+    /// A game on a fixed board using the conventions of [`annotate`]:
+    /// `*` is a mine, anything else a free cell.
+    pub fn from_minefield(minefield: &[&str]) -> Result<Game, GameError>
+    {
+        let cols = minefield.first().map_or(0, |row| row.chars().count());
+        if cols == 0
+        {
+            return Err(GameError::EmptyBoard);
+        }
+        for (row, cells) in minefield.iter().enumerate()
+        {
+            let len = cells.chars().count();
+            if len != cols
+            {
+                return Err(GameError::RaggedRow { row, len, expected: cols });
+            }
+        }
+
+        let mines: Vec<(usize, usize)> = minefield.iter()
+            .enumerate()
+            .flat_map(|(r, row)| row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '*')
+                .map(move |(c, _)| (r, c)))
+            .collect();
+
+        let mut game = Game::new(minefield.len(), cols, mines.len(), 0)?;
+        game.place(&mines);
+        Ok(game)
+    }
+
+
+    pub fn rows(&self) -> usize
+    {
+        self.rows
+    }
+
+
+    pub fn cols(&self) -> usize
+    {
+        self.cols
+    }
+
+
+    pub fn mines(&self) -> usize
+    {
+        self.mines
+    }
+
+
+    pub fn state(&self) -> GameState
+    {
+        self.state
+    }
+
+
+    /// Whether the mines have been placed, i.e. the first reveal happened
+    /// or the board was fixed from the start.
+    pub fn is_placed(&self) -> bool
+    {
+        !self.board.is_empty()
+    }
+
+
+    pub fn cell(&self, row: usize, col: usize) -> Cell
+    {
+        self.cells[row][col]
+    }
+
+
+    /// Whether there is a mine at (`row`, `col`); always false before placement.
+    pub fn is_mine(&self, row: usize, col: usize) -> bool
+    {
+        self.is_placed() && self.board[row][col] == '*'
+    }
+
+
+    /// Number of mines around (`row`, `col`).
+    pub fn adjacent_mines(&self, row: usize, col: usize) -> u8
+    {
+        if !self.is_placed()
+        {
+            return 0;
+        }
+        self.board[row][col].to_digit(10).unwrap_or(0) as u8
+    }
+
+
+    /// The board as [`annotate`] returns it, or nothing before placement.
+    pub fn annotated(&self) -> Vec<String>
+    {
+        self.board.iter().map(|row| row.iter().collect()).collect()
+    }
+
+
+    /// The board as the player sees it, in the conventions of the solver:
+    /// flags as mines and [`HIDDEN`] for the covered cells.
+    pub fn view(&self) -> Vec<String>
+    {
+        (0..self.rows)
+            .map(|r| (0..self.cols)
+                .map(|c| match self.cells[r][c]
+                {
+                    Cell::Hidden => HIDDEN,
+                    Cell::Flagged => '*',
+                    Cell::Revealed => self.board[r][c],
+                })
+                .collect())
+            .collect()
+    }
+
+
+    /// Mines minus flags, as shown on a mine counter.
+    pub fn mines_left(&self) -> isize
+    {
+        let flags = self.cells.iter().flatten().filter(|c| **c == Cell::Flagged).count();
+        self.mines as isize - flags as isize
+    }
+
+
+    /// The up to 8 cells around (`row`, `col`).
+    pub fn neighbours(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)>
+    {
+        let (rows, cols) = (self.rows, self.cols);
+        (row.saturating_sub(1)..=(row + 1).min(rows - 1))
+            .flat_map(move |r| (col.saturating_sub(1)..=(col + 1).min(cols - 1)).map(move |c| (r, c)))
+            .filter(move |(r, c)| (*r, *c) != (row, col))
+    }
+
+
+    /// Reveal a cell, flooding the regions without adjacent mines.
+    /// Returns the newly revealed cells.
+    pub fn reveal(&mut self, row: usize, col: usize) -> Result<Vec<(usize, usize)>, GameError>
+    {
+        self.check(row, col)?;
+        match self.cells[row][col]
+        {
+            Cell::Flagged => return Err(GameError::Flagged),
+            Cell::Revealed => return Ok(Vec::new()),
+            Cell::Hidden => {}
+        }
+
+        if !self.is_placed()
+        {
+            self.place_randomly(row, col);
+        }
+
+        if self.is_mine(row, col)
+        {
+            self.cells[row][col] = Cell::Revealed;
+            self.state = GameState::Lost;
+            return Ok(vec![(row, col)]);
+        }
+
+        let mut opened = Vec::new();
+        let mut queue = VecDeque::from([(row, col)]);
+        while let Some((r, c)) = queue.pop_front()
+        {
+            if self.cells[r][c] != Cell::Hidden
+            {
+                continue;
+            }
+            self.cells[r][c] = Cell::Revealed;
+            self.revealed += 1;
+            opened.push((r, c));
+
+            if self.adjacent_mines(r, c) == 0
+            {
+                queue.extend(self.neighbours(r, c).filter(|(nr, nc)| self.cells[*nr][*nc] == Cell::Hidden));
+            }
+        }
+
+        if self.revealed == self.rows * self.cols - self.mines
+        {
+            self.state = GameState::Won;
+        }
+        Ok(opened)
+    }
+
+
+    /// Flag or unflag a hidden cell. Returns whether the cell is now flagged.
+    pub fn toggle_flag(&mut self, row: usize, col: usize) -> Result<bool, GameError>
+    {
+        self.check(row, col)?;
+        self.cells[row][col] = match self.cells[row][col]
+        {
+            Cell::Hidden => Cell::Flagged,
+            Cell::Flagged => Cell::Hidden,
+            Cell::Revealed => Cell::Revealed,
+        };
+        Ok(self.cells[row][col] == Cell::Flagged)
+    }
+
+
+    /// On a revealed number with as many flags around it, reveal the other neighbours.
+    /// Returns the newly revealed cells.
+    pub fn chord(&mut self, row: usize, col: usize) -> Result<Vec<(usize, usize)>, GameError>
+    {
+        self.check(row, col)?;
+        if self.cells[row][col] != Cell::Revealed
+        {
+            return Ok(Vec::new());
+        }
+
+        let neighbours: Vec<(usize, usize)> = self.neighbours(row, col).collect();
+        let flags = neighbours.iter().filter(|(r, c)| self.cells[*r][*c] == Cell::Flagged).count();
+        if flags != self.adjacent_mines(row, col) as usize
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut opened = Vec::new();
+        for (r, c) in neighbours
+        {
+            if self.state == GameState::Playing && self.cells[r][c] == Cell::Hidden
+            {
+                opened.extend(self.reveal(r, c)?);
+            }
+        }
+        Ok(opened)
+    }
+
+
+    fn check(&self, row: usize, col: usize) -> Result<(), GameError>
+    {
+        if row >= self.rows || col >= self.cols
+        {
+            return Err(GameError::OutOfBounds);
+        }
+        if self.state != GameState::Playing
+        {
+            return Err(GameError::GameOver);
+        }
+        Ok(())
+    }
+
+
+    /// Place the mines avoiding (`row`, `col`) and, if possible, its neighbours.
+    fn place_randomly(&mut self, row: usize, col: usize)
+    {
+        let safe: Vec<(usize, usize)> = self.neighbours(row, col).chain([(row, col)]).collect();
+        let free = self.rows * self.cols - safe.len();
+
+        let mut candidates: Vec<(usize, usize)> = (0..self.rows)
+            .flat_map(|r| (0..self.cols).map(move |c| (r, c)))
+            .filter(|pos| if free >= self.mines { !safe.contains(pos) } else { *pos != (row, col) })
+            .collect();
+        candidates.shuffle(&mut self.rng);
+        candidates.truncate(self.mines);
+        self.place(&candidates);
+    }
+
+
+    fn place(&mut self, mines: &[(usize, usize)])
+    {
+        let mut minefield = vec![vec![' '; self.cols]; self.rows];
+        for (r, c) in mines
+        {
+            minefield[*r][*c] = '*';
+        }
+        let rows: Vec<String> = minefield.iter().map(|row| row.iter().collect()).collect();
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+
+        self.board = annotate(&rows).iter().map(|row| row.chars().collect()).collect();
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::game::{Game, GameError, GameState};
+use crate::solver::Solver;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorError
+{
+    /// The size or the number of mines is not valid for a game.
+    Game(GameError),
+    /// The density is not between 0 and 1.
+    InvalidDensity,
+    /// No board solvable without guessing was found within the time budget.
+    TimedOut { attempts: usize },
+}
+
+
+impl fmt::Display for GeneratorError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            GeneratorError::Game(e) => write!(f, "{}", e),
+            GeneratorError::InvalidDensity => write!(f, "the density must be between 0 and 1"),
+            GeneratorError::TimedOut { attempts } =>
+                write!(f, "no board without guesses found in {} attempts", attempts),
+        }
+    }
+}
+
+
+impl std::error::Error for GeneratorError {}
+
+
+impl From<GameError> for GeneratorError
+{
+    fn from(e: GameError) -> Self
+    {
+        GeneratorError::Game(e)
+    }
+}
+
+
+/// A board that can be cleared from `first_click` by logic alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoGuessBoard
+{
+    /// `*` for mines, a space for the other cells.
+    pub minefield: Vec<String>,
+    pub first_click: (usize, usize),
+    /// Candidates tried, this one included.
+    pub attempts: usize,
+}
+
+
+impl NoGuessBoard
+{
+    /// A new game on this board, with the first click already played.
+    pub fn game(&self) -> Game
+    {
+        let rows: Vec<&str> = self.minefield.iter().map(|row| row.as_str()).collect();
+        let mut game = Game::from_minefield(&rows).unwrap();
+        game.reveal(self.first_click.0, self.first_click.1).unwrap();
+        game
+    }
+}
+
+
+/// Generates boards that never require a guess.
+///
+/// Candidates are placed like the first click of [`Game::new`] and kept only
+/// if the [`Solver`] clears them without guessing. The same seed gives the
+/// same board, as long as it is found within the time budget.
+#[derive(Debug, Clone)]
+pub struct Generator
+{
+    rows: usize,
+    cols: usize,
+    mines: usize,
+    seed: u64,
+    budget: Duration,
+    first_click: (usize, usize),
+}
+
+
+impl Generator
+{
+    pub fn new(rows: usize, cols: usize, mines: usize) -> Result<Generator, GeneratorError>
+    {
+        // Check the size once here rather than at every attempt
+        Game::new(rows, cols, mines, 0)?;
+
+        Ok(Generator
+        {
+            rows,
+            cols,
+            mines,
+            seed: 0,
+            budget: Duration::from_secs(5),
+            first_click: (rows / 2, cols / 2),
+        })
+    }
+
+
+    /// A board with `density` of its cells mined.
+    pub fn with_density(rows: usize, cols: usize, density: f64) -> Result<Generator, GeneratorError>
+    {
+        if !(0.0..1.0).contains(&density)
+        {
+            return Err(GeneratorError::InvalidDensity);
+        }
+        Generator::new(rows, cols, ((rows * cols) as f64 * density).round() as usize)
+    }
+
+
+    pub fn seed(mut self, seed: u64) -> Self
+    {
+        self.seed = seed;
+        self
+    }
+
+
+    /// How long to keep trying; at least one board is always tried.
+    pub fn time_budget(mut self, budget: Duration) -> Self
+    {
+        self.budget = budget;
+        self
+    }
+
+
+    /// Where the player starts, the middle of the board by default.
+    pub fn first_click(mut self, row: usize, col: usize) -> Result<Self, GeneratorError>
+    {
+        if row >= self.rows || col >= self.cols
+        {
+            return Err(GeneratorError::Game(GameError::OutOfBounds));
+        }
+        self.first_click = (row, col);
+        Ok(self)
+    }
+
+
+    pub fn generate(&self) -> Result<NoGuessBoard, GeneratorError>
+    {
+        let start = Instant::now();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut attempts = 0;
+
+        loop
+        {
+            attempts += 1;
+            let mut game = Game::new(self.rows, self.cols, self.mines, rng.gen())?;
+            game.reveal(self.first_click.0, self.first_click.1)?;
+
+            if solve(&mut game)
+            {
+                let minefield = game.annotated()
+                    .iter()
+                    .map(|row| row.chars().map(|c| if c == '*' { '*' } else { ' ' }).collect())
+                    .collect();
+                return Ok(NoGuessBoard { minefield, first_click: self.first_click, attempts });
+            }
+            if start.elapsed() >= self.budget
+            {
+                return Err(GeneratorError::TimedOut { attempts });
+            }
+        }
+    }
+}
+
+
+/// Play `game` using only what the solver can prove, taking flags as mines.
+/// Returns whether it was won.
+pub fn solve(game: &mut Game) -> bool
+{
+    while game.state() == GameState::Playing
+    {
+        let view = game.view();
+        let view: Vec<&str> = view.iter().map(|row| row.as_str()).collect();
+        let deductions = match Solver::new(&view).map(|s| s.with_mines(game.mines()).deduce())
+        {
+            Ok(Ok(deductions)) => deductions,
+            _ => return false,
+        };
+        if deductions.safe.is_empty()
+        {
+            return false;
+        }
+
+        for (row, col) in deductions.mines
+        {
+            let _ = game.toggle_flag(row, col);
+        }
+        for (row, col) in deductions.safe
+        {
+            if game.state() == GameState::Playing
+            {
+                let _ = game.reveal(row, col);
+            }
+        }
+    }
+    game.state() == GameState::Won
+}
@@ -1,112 +1,93 @@
-pub fn annotate(minefield: &[&str]) -> Vec<String> 
-{
-    let mut new_mf: Vec<String> = Vec::new();
-    
-    if !minefield.is_empty()
-    {
-        // Casi "banali"
-        if minefield.iter().any(|row: &&str| (*row).is_empty()) 
-            || match_all_characters(&minefield, '*')
-            || match_all_characters(&minefield, ' ')
-        {
-            for i in 0..minefield.len()
-            {
-                new_mf.push((*minefield[i]).to_string());
-            }
-        }
-        
-        // Annotazione
-        for i in 0..minefield.len()
-        {
-            let mut ann_line = lookup_same_line(&minefield[i].chars().collect());
-            if i > 0
-            {
-                ann_line = lookup_above_bottom(&ann_line, &minefield[i-1].chars().collect());
-            }
-            if i < minefield.len() - 1
-            {
-                ann_line = lookup_above_bottom(&ann_line, &minefield[i+1].chars().collect());
-            }
-            new_mf.push(ann_line.iter().collect());
-        }    
-    }
-    new_mf   
-}
+pub mod board;
+pub mod bytes;
+pub mod game;
+pub mod generator;
+pub mod replay;
+pub mod solver;
+pub mod topology;
+
+use board::BoardError;
+use topology::{count_mines, Rectangular, Topology};
 
 
-fn match_all_characters(minefield: &&[&str], ch: char) -> bool
+/// Add the number of adjacent mines to each free cell of `minefield`.
+///
+/// # Panics
+///
+/// If the rows have different lengths or contain anything but `*` and
+/// spaces; [`try_annotate`] reports these as errors instead.
+pub fn annotate(minefield: &[&str]) -> Vec<String> 
 {
-    minefield.iter()
-        .flat_map(|row| (*row).chars())
-        .all(|c| c == ch)
-    
+    try_annotate(minefield).unwrap_or_else(|e| panic!("{}", e))
 }
 
 
-fn lookup_same_line(line: &Vec<char>) -> Vec<char>
+/// Like [`annotate`], checking the board first.
+pub fn try_annotate(minefield: &[&str]) -> Result<Vec<String>, BoardError>
 {
-    let mut ann_line: Vec<char> = Vec::new();
-
-    for i in 0..line.len()
+    let cols = minefield.first().map_or(0, |row| row.chars().count());
+    for (row, line) in minefield.iter().enumerate()
     {
-        if line[i] == ' ' || line[i].is_numeric()
+        let len = line.chars().count();
+        if len != cols
         {
-            let mut count: u32 = 0;
-            if line[i].is_numeric()
-            {
-                count = char::to_digit(line[i], 10).unwrap();
-            }
-            if i > 0 && line[i-1] == '*'
-            {
-                count += 1;
-            }
-            if i < line.len() - 1 && line[i+1] == '*'
-            {
-                count += 1;
-            }
-            ann_line.push(char::from_digit(count, 10).unwrap());
-        }
-        else if line[i] == '*'
-        {
-            ann_line.push(line[i]);
+            return Err(BoardError::RaggedRow { row, len, expected: cols });
         }
     }
-    ann_line
+    annotate_on(&Rectangular::new(minefield.len(), cols), minefield)
 }
 
 
-fn lookup_above_bottom(current: &Vec<char>, other: &Vec<char>) -> Vec<char>
+/// Annotate `minefield` on any [`Topology`]. Its rows, read one after the
+/// other, hold the cells in the order of the topology; the rows of the
+/// result have the same lengths.
+///
+/// Counts past 9, possible in three dimensions, are written as letters:
+/// `a` for 10 up to `q` for 26.
+pub fn annotate_on<T: Topology + ?Sized>(topology: &T, minefield: &[&str]) -> Result<Vec<String>, BoardError>
 {
-    let mut ann_line: Vec<char> = Vec::new();
-
-    for i in 0..current.len()
+    // Only `*` and spaces are allowed, so each cell is one byte
+    let mut cells = Vec::with_capacity(topology.cells());
+    for (row, line) in minefield.iter().enumerate()
     {
-        if current[i] == ' ' || current[i].is_numeric()
+        // Without branches on the cells, then look for the culprit only if needed
+        if !line.bytes().fold(true, |valid, b| valid & (b == b'*' || b == b' '))
         {
-            let mut count: u32 = 0;
-            if current[i].is_numeric()
-            {
-                count = char::to_digit(current[i], 10).unwrap();
-            }
-            if other[i] == '*'
-            {
-                count += 1;
-            }
-            if i > 0 && other[i-1] == '*'
-            {
-                count += 1;
-            }
-            if i < other.len() - 1 && other[i+1] == '*'
-            {
-                count += 1;
-            }
-            ann_line.push(char::from_digit(count, 10).unwrap());
+            let (col, ch) = line.chars().enumerate().find(|(_, ch)| *ch != '*' && *ch != ' ').unwrap();
+            return Err(BoardError::InvalidCharacter { row, col, ch });
         }
-        else if current[i] == '*'
+        cells.extend_from_slice(line.as_bytes());
+    }
+    if cells.len() != topology.cells()
+    {
+        return Err(BoardError::SizeMismatch { cells: cells.len(), expected: topology.cells() });
+    }
+
+    let annotated = match topology.grid()
+    {
+        Some((_, cols)) => bytes::annotate_grid(&cells, cols)?,
+        None =>
         {
-            ann_line.push(current[i]);
+            let mines: Vec<bool> = cells.iter().map(|b| *b == b'*').collect();
+            count_mines(topology, &mines).into_iter()
+                .map(|count| match count
+                {
+                    None => b'*',
+                    // Le celle senza mine adiacenti restano vuote
+                    Some(n) => bytes::symbol(n),
+                })
+                .collect()
         }
-    }
+    };
 
-    ann_line
+    let mut rest = annotated.as_slice();
+    Ok(minefield.iter()
+        .map(|line|
+        {
+            let (row, tail) = rest.split_at(line.len());
+            rest = tail;
+            // Only `*`, spaces, digits and letters are left
+            String::from_utf8(row.to_vec()).unwrap()
+        })
+        .collect())
 }
@@ -1,33 +1,182 @@
+use std::error::Error;
 use std::fmt::Display;
-use clap::Parser;
-use minesweeper::annotate;
+use std::fs;
+use std::io::{stdout, Write};
+use std::path::PathBuf;
+use clap::{Parser, ValueEnum};
+use minesweeper::annotate_on;
+use minesweeper::board::{Board, Format};
+use minesweeper::solver::{Hint, Solver};
+use minesweeper::topology::{Cubic, Hexagonal, Toroidal};
+
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum Shape
+{
+    #[default]
+    Rectangular,
+    /// Edges wrap around
+    Toroidal,
+    /// Odd rows shifted half a cell to the right
+    Hexagonal,
+}
+
+
+/// Annotate a minefield given as a flat string or read from a file,
+/// convert it to another format, or with --hint suggest the next move
+/// on a board where `?` marks the hidden cells.
+#[derive(Parser, Default, Debug)]
+struct Arguments
+{
+    #[arg(required_unless_present = "file")]
+    rows: Option<usize>,
+    #[arg(required_unless_present = "file")]
+    cols: Option<usize>,
+    #[arg(required_unless_present = "file")]
+    field: Option<String>,
+
+    /// Read the board from a file: text, .mbf or .json
+    #[arg(short, long, conflicts_with_all = ["rows", "cols", "field"])]
+    file: Option<PathBuf>,
+
+    /// Format of the file, guessed from its extension by default
+    #[arg(long, requires = "file")]
+    format: Option<Format>,
+
+    /// Write the board in this format (text, mbf or json) instead of annotating it
+    #[arg(long, conflicts_with = "hint")]
+    to: Option<Format>,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Layout of the cells when annotating
+    #[arg(long, value_enum, default_value_t)]
+    topology: Shape,
+
+    /// Annotate a cube: the rows are split into this many stacked layers
+    #[arg(long, conflicts_with = "topology")]
+    layers: Option<usize>,
+
+    /// Suggest the next move and show the cells that are certain
+    #[arg(long, conflicts_with_all = ["format", "output", "topology", "layers"])]
+    hint: bool,
+
+    /// Total number of mines, known ones included
+    #[arg(long, requires = "hint")]
+    mines: Option<usize>,
+}
+
 
 fn main()
 {
-    #[derive(Parser, Default, Debug)]
-    struct Arguments
+    let args = Arguments::parse();
+    if let Err(e) = run(&args)
     {
-        rows: usize,
-        cols: usize,
-        field: String,
+        eprintln!("minesweeper: {}", e);
+        std::process::exit(1);
     }
+}
 
-    let args = Arguments::parse();
-    assert_eq!(args.field.len(), args.rows * args.cols);
 
-    let mut inf: usize;
-    let mut sup: usize;
-    let mut field: Vec<&str> = Vec::new();
+fn run(args: &Arguments) -> Result<(), Box<dyn Error>>
+{
+    let (data, format) = match &args.file
+    {
+        Some(path) => (fs::read(path)?, args.format.unwrap_or_else(|| Format::from_path(path))),
+        None =>
+        {
+            let (rows, cols, field) = (args.rows.unwrap(), args.cols.unwrap(), args.field.as_ref().unwrap());
+            let cells: Vec<char> = field.chars().collect();
+            if rows.checked_mul(cols) != Some(cells.len())
+            {
+                return Err(format!("the field has {} cells, expected {} x {}", cells.len(), rows, cols).into());
+            }
+            let text: String = cells.chunks(cols.max(1)).map(|row| row.iter().collect::<String>() + "\n").collect();
+            (text.into_bytes(), Format::Text)
+        }
+    };
+
+    if args.hint
+    {
+        // Hidden cells are not part of any board format
+        let text = String::from_utf8(data)?;
+        let field: Vec<&str> = text.lines().collect();
+        return print_hint(&field, args.mines);
+    }
 
-    for off in 0..args.rows
+    let board = Board::parse(&data, format)?;
+    let out = match args.to
+    {
+        Some(format) => board.serialize(format)?,
+        None => annotate(&board, args)?.iter().map(|row| row.clone() + "\n").collect::<String>().into_bytes(),
+    };
+    match &args.output
     {
-        inf = off * args.cols;
-        sup = inf + args.cols;
-        field.push(&args.field[inf..sup]);
+        Some(path) => fs::write(path, out)?,
+        None => stdout().write_all(&out)?,
     }
+    Ok(())
+}
+
+
+fn annotate(board: &Board, args: &Arguments) -> Result<Vec<String>, Box<dyn Error>>
+{
+    let minefield = board.minefield();
+    let minefield: Vec<&str> = minefield.iter().map(|row| row.as_str()).collect();
+    let (rows, cols) = (board.rows(), board.cols());
 
-    let ann_field = annotate(&field);
-    print_vec(&ann_field);
+    let annotated = match (args.layers, args.topology)
+    {
+        (Some(layers), _) =>
+        {
+            if layers == 0 || rows % layers != 0
+            {
+                return Err(format!("{} rows cannot be split into {} layers", rows, layers).into());
+            }
+            annotate_on(&Cubic::new(layers, rows / layers, cols), &minefield)?
+        }
+        (None, Shape::Rectangular) => board.annotate(),
+        (None, Shape::Toroidal) => annotate_on(&Toroidal::new(rows, cols), &minefield)?,
+        (None, Shape::Hexagonal) => annotate_on(&Hexagonal::new(rows, cols), &minefield)?,
+    };
+    Ok(annotated)
+}
+
+
+/// Print the board with the certain cells marked, `o` free and `!` mines,
+/// followed by the suggested move.
+fn print_hint(field: &[&str], mines: Option<usize>) -> Result<(), Box<dyn Error>>
+{
+    let mut solver = Solver::new(field)?;
+    if let Some(total) = mines
+    {
+        solver = solver.with_mines(total);
+    }
+
+    let deductions = solver.deduce()?;
+    let mut board: Vec<Vec<char>> = field.iter().map(|row| row.chars().collect()).collect();
+    for (r, c) in deductions.safe.iter()
+    {
+        board[*r][*c] = 'o';
+    }
+    for (r, c) in deductions.mines.iter()
+    {
+        board[*r][*c] = '!';
+    }
+    print_vec(&board.iter().map(|row| row.iter().collect::<String>()).collect());
+
+    match solver.hint()?
+    {
+        Some(Hint::Safe(r, c)) => println!("Hint: reveal ({}, {})", r, c),
+        Some(Hint::Mine(r, c)) => println!("Hint: flag ({}, {})", r, c),
+        Some(Hint::Guess { row, col, probability: Some(p) }) =>
+            println!("Hint: guess ({}, {}), mine probability {:.1}%", row, col, p * 100.0),
+        Some(Hint::Guess { row, col, probability: None }) => println!("Hint: guess ({}, {})", row, col),
+        None => println!("Hint: nothing left to do"),
+    }
+    Ok(())
 }
 
 
@@ -37,4 +186,4 @@ pub fn print_vec<T : Display>(vec: &Vec<T>)
     {
         println!("{}", el);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,386 @@
+//! Recording of games and their deterministic playback.
+//!
+//! A replay file starts with `MSRP` and a version byte, followed by the
+//! board in the MBF layout of [`Format::Mbf`](crate::board::Format::Mbf).
+//! Then come the moves until the end of the data, each as an unsigned
+//! LEB128 number holding the milliseconds since the previous move shifted
+//! left by two with the action in the low bits, then the column and the
+//! row of the cell, one byte each. Most moves take 3 or 4 bytes.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::board::{Board, BoardError};
+use crate::game::{Cell, Game, GameError, GameState};
+
+
+const MAGIC: &[u8; 4] = b"MSRP";
+const VERSION: u8 = 1;
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError
+{
+    /// The data does not start like a replay file.
+    NotAReplay,
+    UnsupportedVersion(u8),
+    /// The board could not be read or written.
+    Board(BoardError),
+    /// The data ends in the middle of a move.
+    Truncated,
+    /// The move starting at `offset` has an unknown action or a time too large.
+    InvalidMove { offset: usize },
+    /// No game can be played on the board, as when every cell is mined.
+    Game(GameError),
+    /// The move number `index`, from 0, is rejected by the game.
+    IllegalMove { index: usize, error: GameError },
+    /// The mines are not placed until the first reveal, so there is no board yet.
+    NotStarted,
+}
+
+
+impl fmt::Display for ReplayError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            ReplayError::NotAReplay => write!(f, "not a replay file"),
+            ReplayError::UnsupportedVersion(v) => write!(f, "unsupported replay version {}", v),
+            ReplayError::Board(e) => write!(f, "{}", e),
+            ReplayError::Truncated => write!(f, "the replay ends in the middle of a move"),
+            ReplayError::InvalidMove { offset } => write!(f, "invalid move at byte {}", offset),
+            ReplayError::Game(e) => write!(f, "{}", e),
+            ReplayError::IllegalMove { index, error } => write!(f, "move {}: {}", index + 1, error),
+            ReplayError::NotStarted => write!(f, "the game has not started"),
+        }
+    }
+}
+
+
+impl std::error::Error for ReplayError {}
+
+
+impl From<BoardError> for ReplayError
+{
+    fn from(e: BoardError) -> Self
+    {
+        ReplayError::Board(e)
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action
+{
+    Reveal,
+    Flag,
+    Chord,
+}
+
+
+/// An action of the player on a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move
+{
+    pub action: Action,
+    pub row: usize,
+    pub col: usize,
+    /// Time since the start of the game, in whole milliseconds once saved.
+    pub time: Duration,
+}
+
+
+impl Move
+{
+    pub fn new(action: Action, row: usize, col: usize, time: Duration) -> Self
+    {
+        Move { action, row, col, time }
+    }
+
+
+    /// Play the move on `game`. Returns the newly revealed cells.
+    pub fn apply(&self, game: &mut Game) -> Result<Vec<(usize, usize)>, GameError>
+    {
+        match self.action
+        {
+            Action::Reveal => game.reveal(self.row, self.col),
+            Action::Flag => game.toggle_flag(self.row, self.col).map(|_| Vec::new()),
+            Action::Chord => game.chord(self.row, self.col),
+        }
+    }
+}
+
+
+/// A game as it was played: the board and every move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay
+{
+    pub board: Board,
+    pub moves: Vec<Move>,
+}
+
+
+impl Replay
+{
+    /// The replay of `game`, once its mines are placed, played with `moves`.
+    pub fn new(game: &Game, moves: Vec<Move>) -> Result<Replay, ReplayError>
+    {
+        if !game.is_placed()
+        {
+            return Err(ReplayError::NotStarted);
+        }
+        let mines: Vec<(usize, usize)> = (0..game.rows())
+            .flat_map(|r| (0..game.cols()).map(move |c| (r, c)))
+            .filter(|(r, c)| game.is_mine(*r, *c))
+            .collect();
+        Ok(Replay { board: Board::new(game.rows(), game.cols(), &mines)?, moves })
+    }
+
+
+    pub fn from_bytes(data: &[u8]) -> Result<Replay, ReplayError>
+    {
+        if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC
+        {
+            return Err(ReplayError::NotAReplay);
+        }
+        if data[MAGIC.len()] != VERSION
+        {
+            return Err(ReplayError::UnsupportedVersion(data[MAGIC.len()]));
+        }
+
+        // The MBF header tells where the board ends
+        let start = MAGIC.len() + 1;
+        let header = data.get(start..start + 4).ok_or(ReplayError::Truncated)?;
+        let end = start + 4 + 2 * u16::from_be_bytes([header[2], header[3]]) as usize;
+        let board = Board::from_mbf(data.get(start..end).ok_or(ReplayError::Truncated)?)?;
+
+        let mut moves = Vec::new();
+        let mut time = Duration::ZERO;
+        let mut pos = end;
+        while pos < data.len()
+        {
+            let offset = pos;
+            let mut value: u64 = 0;
+            let mut shift = 0;
+            loop
+            {
+                let b = *data.get(pos).ok_or(ReplayError::Truncated)?;
+                pos += 1;
+                if shift > 63 || (shift == 63 && b > 1)
+                {
+                    return Err(ReplayError::InvalidMove { offset });
+                }
+                value |= ((b & 0x7f) as u64) << shift;
+                if b & 0x80 == 0
+                {
+                    break;
+                }
+                shift += 7;
+            }
+
+            let action = match value & 3
+            {
+                0 => Action::Reveal,
+                1 => Action::Flag,
+                2 => Action::Chord,
+                _ => return Err(ReplayError::InvalidMove { offset }),
+            };
+            let cell = data.get(pos..pos + 2).ok_or(ReplayError::Truncated)?;
+            pos += 2;
+            time = time.saturating_add(Duration::from_millis(value >> 2));
+            moves.push(Move::new(action, cell[1] as usize, cell[0] as usize, time));
+        }
+        Ok(Replay { board, moves })
+    }
+
+
+    /// The replay in the compact format described in the module; boards
+    /// beyond 255 x 255 do not fit.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError>
+    {
+        let mut data = MAGIC.to_vec();
+        data.push(VERSION);
+        data.extend(self.board.to_mbf()?);
+
+        let mut last = 0;
+        for m in &self.moves
+        {
+            if m.row > 255 || m.col > 255
+            {
+                return Err(ReplayError::Board(BoardError::TooLarge));
+            }
+            let time = m.time.as_millis().min(u64::MAX as u128 >> 2) as u64;
+            let action = match m.action
+            {
+                Action::Reveal => 0,
+                Action::Flag => 1,
+                Action::Chord => 2,
+            };
+            let mut value = time.saturating_sub(last) << 2 | action;
+            last = time;
+
+            while value >= 0x80
+            {
+                data.push(value as u8 | 0x80);
+                value >>= 7;
+            }
+            data.push(value as u8);
+            data.extend([m.col as u8, m.row as u8]);
+        }
+        Ok(data)
+    }
+
+
+    /// A new game on the board of the replay, before the first move.
+    pub fn game(&self) -> Result<Game, ReplayError>
+    {
+        let minefield = self.board.minefield();
+        let rows: Vec<&str> = minefield.iter().map(|row| row.as_str()).collect();
+        Game::from_minefield(&rows).map_err(ReplayError::Game)
+    }
+
+
+    /// The game after all the moves.
+    pub fn play(&self) -> Result<Game, ReplayError>
+    {
+        let mut game = self.game()?;
+        for (index, m) in self.moves.iter().enumerate()
+        {
+            m.apply(&mut game).map_err(|error| ReplayError::IllegalMove { index, error })?;
+        }
+        Ok(game)
+    }
+
+
+    pub fn stats(&self) -> Result<Stats, ReplayError>
+    {
+        Ok(Stats::new(&self.play()?, &self.moves))
+    }
+}
+
+
+/// How well a game was played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats
+{
+    /// The 3BV of the board: the fewest clicks that clear it.
+    pub bbbv: usize,
+    /// The part of the 3BV that was cleared.
+    pub solved_bbbv: usize,
+    /// Every move counts as a click: reveals, flags and chords.
+    pub clicks: usize,
+    /// Time of the last move.
+    pub time: Duration,
+    pub state: GameState,
+}
+
+
+impl Stats
+{
+    /// The statistics of `game` after `moves`.
+    pub fn new(game: &Game, moves: &[Move]) -> Stats
+    {
+        let annotated = game.annotated();
+        let board: Vec<&str> = annotated.iter().map(|row| row.as_str()).collect();
+        Stats
+        {
+            bbbv: count_clicks(&board, |_, _| true),
+            solved_bbbv: count_clicks(&board, |r, c| game.cell(r, c) == Cell::Revealed),
+            clicks: moves.len(),
+            time: moves.last().map_or(Duration::ZERO, |m| m.time),
+            state: game.state(),
+        }
+    }
+
+
+    /// Cleared 3BV per click; past 1 thanks to chords.
+    pub fn efficiency(&self) -> f64
+    {
+        if self.clicks == 0
+        {
+            return 0.0;
+        }
+        self.solved_bbbv as f64 / self.clicks as f64
+    }
+
+
+    /// Cleared 3BV per second.
+    pub fn bbbv_per_second(&self) -> f64
+    {
+        if self.time.is_zero()
+        {
+            return 0.0;
+        }
+        self.solved_bbbv as f64 / self.time.as_secs_f64()
+    }
+}
+
+
+/// The 3BV of a board as [`annotate`](crate::annotate) returns it: one
+/// click for each opening, the area of cells without adjacent mines, and
+/// one for each number not on the edge of an opening.
+pub fn three_bv(annotated: &[&str]) -> usize
+{
+    count_clicks(annotated, |_, _| true)
+}
+
+
+/// The 3BV counting only the openings with a cell, and the numbers, for which
+/// `cleared` holds.
+fn count_clicks(annotated: &[&str], cleared: impl Fn(usize, usize) -> bool) -> usize
+{
+    let board: Vec<&[u8]> = annotated.iter().map(|row| row.as_bytes()).collect();
+    let rows = board.len();
+    let mut seen: Vec<Vec<bool>> = board.iter().map(|row| vec![false; row.len()]).collect();
+    let neighbours = |r: usize, c: usize|
+    {
+        let cols = board[r].len();
+        (r.saturating_sub(1)..=(r + 1).min(rows - 1))
+            .flat_map(move |nr| (c.saturating_sub(1)..=(c + 1).min(cols - 1)).map(move |nc| (nr, nc)))
+    };
+    let mut clicks = 0;
+
+    for r in 0..rows
+    {
+        for c in 0..board[r].len()
+        {
+            if board[r][c] != b' ' || seen[r][c]
+            {
+                continue;
+            }
+            // The opening and the numbers around it go with one click
+            let mut opened = false;
+            let mut stack = vec![(r, c)];
+            seen[r][c] = true;
+            while let Some((r, c)) = stack.pop()
+            {
+                opened |= cleared(r, c);
+                for (nr, nc) in neighbours(r, c)
+                {
+                    if !seen[nr][nc]
+                    {
+                        seen[nr][nc] = true;
+                        if board[nr][nc] == b' '
+                        {
+                            stack.push((nr, nc));
+                        }
+                    }
+                }
+            }
+            clicks += opened as usize;
+        }
+    }
+
+    for r in 0..rows
+    {
+        for c in 0..board[r].len()
+        {
+            if board[r][c] != b'*' && !seen[r][c] && cleared(r, c)
+            {
+                clicks += 1;
+            }
+        }
+    }
+    clicks
+}
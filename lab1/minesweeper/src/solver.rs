@@ -0,0 +1,740 @@
+use std::collections::HashSet;
+use std::fmt;
+
+
+/// A cell that has not been revealed yet.
+pub const HIDDEN: char = '?';
+
+/// Past this many constraints the subset reasoning gives up.
+const MAX_CONSTRAINTS: usize = 3000;
+
+/// Past this many steps counting the placements of a group of cells, the
+/// exact probabilities give up, unless told otherwise. About 0.1s.
+const MAX_STEPS: usize = 1_000_000;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverError
+{
+    EmptyBoard,
+    /// The row is not as long as the first one.
+    RaggedRow { row: usize },
+    InvalidCharacter { row: usize, col: usize, ch: char },
+    /// No placement of the mines agrees with the board.
+    Inconsistent,
+    /// The mines can be placed in too many ways to count them all.
+    TooComplex,
+}
+
+
+impl fmt::Display for SolverError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SolverError::EmptyBoard => write!(f, "the board has no cells"),
+            SolverError::RaggedRow { row } => write!(f, "row {} has a different length", row + 1),
+            SolverError::InvalidCharacter { row, col, ch } =>
+                write!(f, "invalid character {:?} at row {}, column {}", ch, row + 1, col + 1),
+            SolverError::Inconsistent => write!(f, "no placement of the mines agrees with the board"),
+            SolverError::TooComplex => write!(f, "too many placements of the mines to count"),
+        }
+    }
+}
+
+
+impl std::error::Error for SolverError {}
+
+
+/// What the player could do next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint
+{
+    /// The cell is certainly free.
+    Safe(usize, usize),
+    /// The cell is certainly a mine.
+    Mine(usize, usize),
+    /// Nothing is certain: the cell least likely to hold a mine, with its
+    /// probability if it can be computed.
+    Guess { row: usize, col: usize, probability: Option<f64> },
+}
+
+
+/// Cells proven free or mined, in row-major order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deductions
+{
+    pub safe: Vec<(usize, usize)>,
+    pub mines: Vec<(usize, usize)>,
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Square
+{
+    Revealed(u8),
+    Mine,
+    Hidden,
+}
+
+
+/// "Exactly `mines` of `cells` are mines", with `cells` sorted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Constraint
+{
+    cells: Vec<usize>,
+    mines: usize,
+}
+
+
+/// Solver for a partially revealed board.
+///
+/// The board follows the conventions of [`crate::annotate`]: `*` is a known
+/// mine, a digit or a space a revealed cell, plus [`HIDDEN`] for the cells
+/// still covered.
+#[derive(Debug, Clone)]
+pub struct Solver
+{
+    rows: usize,
+    cols: usize,
+    squares: Vec<Square>,
+    mines: Option<usize>,
+    step_limit: usize,
+}
+
+
+impl Solver
+{
+    pub fn new(board: &[&str]) -> Result<Solver, SolverError>
+    {
+        let cols = board.first().map_or(0, |row| row.chars().count());
+        if cols == 0
+        {
+            return Err(SolverError::EmptyBoard);
+        }
+
+        let mut squares = Vec::with_capacity(board.len() * cols);
+        for (row, line) in board.iter().enumerate()
+        {
+            if line.chars().count() != cols
+            {
+                return Err(SolverError::RaggedRow { row });
+            }
+            for (col, ch) in line.chars().enumerate()
+            {
+                squares.push(match ch
+                {
+                    '*' => Square::Mine,
+                    ' ' => Square::Revealed(0),
+                    HIDDEN => Square::Hidden,
+                    '0'..='8' => Square::Revealed(ch as u8 - b'0'),
+                    _ => return Err(SolverError::InvalidCharacter { row, col, ch }),
+                });
+            }
+        }
+
+        Ok(Solver { rows: board.len(), cols, squares, mines: None, step_limit: MAX_STEPS })
+    }
+
+
+    /// Take into account that the board has `total` mines, known ones included.
+    pub fn with_mines(mut self, total: usize) -> Self
+    {
+        self.mines = Some(total);
+        self
+    }
+
+
+    /// Give up on the exact probabilities of a group of cells after `steps`
+    /// steps counting its placements, a million by default.
+    pub fn with_step_limit(mut self, steps: usize) -> Self
+    {
+        self.step_limit = steps;
+        self
+    }
+
+
+    /// Cells that are certainly free or certainly mines, found by propagating
+    /// the numbers, comparing constraints that contain or overlap one another
+    /// and, with [`Self::with_mines`], counting the mines left.
+    pub fn deduce(&self) -> Result<Deductions, SolverError>
+    {
+        let initial = self.initial();
+        let mut known = initial.clone();
+
+        'rebuild: loop
+        {
+            let mut set = self.constraints(&known, true)?;
+            let mut seen: HashSet<Constraint> = set.iter().cloned().collect();
+
+            loop
+            {
+                let mut progress = false;
+                for constraint in set.iter()
+                {
+                    if constraint.mines == 0 || constraint.mines == constraint.cells.len()
+                    {
+                        for &cell in constraint.cells.iter()
+                        {
+                            let mine = constraint.mines > 0;
+                            match known[cell]
+                            {
+                                None => known[cell] = Some(mine),
+                                Some(m) if m != mine => return Err(SolverError::Inconsistent),
+                                Some(_) => continue,
+                            }
+                            progress = true;
+                        }
+                    }
+                }
+                if progress
+                {
+                    continue 'rebuild;
+                }
+
+                // Compare the constraints sharing a cell
+                let mut containing: Vec<Vec<usize>> = vec![Vec::new(); known.len()];
+                for (i, constraint) in set.iter().enumerate()
+                {
+                    for &cell in constraint.cells.iter()
+                    {
+                        containing[cell].push(i);
+                    }
+                }
+                let unknown = known.iter().filter(|k| k.is_none()).count();
+                let mut derived = Vec::new();
+                for (i, a) in set.iter().enumerate()
+                {
+                    let mut others: Vec<usize> = a.cells.iter().flat_map(|cell| containing[*cell].iter().copied()).collect();
+                    others.sort_unstable();
+                    others.dedup();
+
+                    for b in others.into_iter().filter(|j| *j != i).map(|j| &set[j])
+                    {
+                        let only_a: Vec<usize> = a.cells.iter().filter(|c| b.cells.binary_search(c).is_err()).copied().collect();
+                        let only_b: Vec<usize> = b.cells.iter().filter(|c| a.cells.binary_search(c).is_err()).copied().collect();
+
+                        // A constraint inside another one settles the cells in between
+                        let found = if only_a.is_empty()
+                        {
+                            let mines = b.mines.checked_sub(a.mines).ok_or(SolverError::Inconsistent)?;
+                            vec![Constraint { cells: only_b, mines }]
+                        }
+                        // If the cells of `a` outside `b` cannot hold all of its mines, the
+                        // rest fall in the overlap: when that is all of `b`'s, the rest of `b` is free
+                        else if a.mines > only_a.len() && a.mines - only_a.len() == b.mines
+                        {
+                            let mines = only_a.len();
+                            vec![Constraint { cells: only_b, mines: 0 }, Constraint { cells: only_a, mines }]
+                        }
+                        else
+                        {
+                            continue;
+                        };
+
+                        for c in found
+                        {
+                            if c.mines > c.cells.len()
+                            {
+                                return Err(SolverError::Inconsistent);
+                            }
+                            // Larger ones would multiply without end, unless they settle every cell
+                            let useful = c.cells.len() <= 8 || c.mines == 0 || c.mines == c.cells.len();
+                            if !c.cells.is_empty() && useful && seen.insert(c.clone())
+                            {
+                                derived.push(c);
+                            }
+                        }
+                    }
+                }
+
+                // Mine counting: what disjoint constraints leave to the rest of the board
+                if let Some(global) = set.iter().find(|c| c.cells.len() == unknown)
+                {
+                    let mut local: Vec<&Constraint> = set.iter().filter(|c| c.cells.len() < unknown).collect();
+                    local.sort_by_key(|c| c.cells.len());
+                    let mut taken = vec![false; known.len()];
+                    let mut mines = 0;
+                    for constraint in local
+                    {
+                        if constraint.cells.iter().all(|cell| !taken[*cell])
+                        {
+                            constraint.cells.iter().for_each(|cell| taken[*cell] = true);
+                            mines += constraint.mines;
+                        }
+                    }
+
+                    let cells: Vec<usize> = global.cells.iter().filter(|cell| !taken[**cell]).copied().collect();
+                    let mines = global.mines.checked_sub(mines).ok_or(SolverError::Inconsistent)?;
+                    if mines > cells.len()
+                    {
+                        return Err(SolverError::Inconsistent);
+                    }
+                    let rest = Constraint { cells, mines };
+                    if !rest.cells.is_empty() && seen.insert(rest.clone())
+                    {
+                        derived.push(rest);
+                    }
+                }
+                if derived.is_empty() || set.len() > MAX_CONSTRAINTS
+                {
+                    break 'rebuild;
+                }
+                set.extend(derived);
+            }
+        }
+
+        let mut deductions = Deductions::default();
+        for (cell, (before, after)) in initial.iter().zip(known.iter()).enumerate()
+        {
+            match (before, after)
+            {
+                (None, Some(false)) => deductions.safe.push(self.position(cell)),
+                (None, Some(true)) => deductions.mines.push(self.position(cell)),
+                _ => {}
+            }
+        }
+        Ok(deductions)
+    }
+
+
+    /// Exact probability that each cell is a mine, counting every placement of
+    /// the mines that agrees with the board as equally likely.
+    ///
+    /// Revealed cells are 0 and known mines 1. Without the total number of
+    /// mines, cells away from the revealed numbers have no probability.
+    /// Counting the placements takes exponential time in the worst case: on
+    /// large open frontiers it stops with [`SolverError::TooComplex`], see
+    /// [`Self::with_step_limit`].
+    pub fn probabilities(&self) -> Result<Vec<Vec<Option<f64>>>, SolverError>
+    {
+        let known = self.initial();
+        let constraints = self.constraints(&known, false)?;
+
+        let mut result: Vec<Option<f64>> = known.iter().map(|k| k.map(|mine| if mine { 1.0 } else { 0.0 })).collect();
+
+        let components = components(&constraints, self.squares.len());
+        let in_frontier: Vec<bool> = (0..self.squares.len())
+            .map(|cell| components.iter().any(|(cells, _)| cells.contains(&cell)))
+            .collect();
+        let others: Vec<usize> = (0..self.squares.len())
+            .filter(|cell| known[*cell].is_none() && !in_frontier[*cell])
+            .collect();
+
+        // Per component, per number of mines: placements and placements with a mine in each cell
+        let mut solutions = Vec::with_capacity(components.len());
+        for (cells, indices) in components.iter()
+        {
+            let chosen: Vec<&Constraint> = indices.iter().map(|i| &constraints[*i]).collect();
+            let dist = enumerate(cells, &chosen, self.step_limit).ok_or(SolverError::TooComplex)?;
+            // Relative to the most common number of mines, so that the products
+            // of many components stay finite
+            let most = dist.iter().map(|(count, _)| *count).fold(0.0, f64::max);
+            if most == 0.0
+            {
+                return Err(SolverError::Inconsistent);
+            }
+            solutions.push(dist.into_iter()
+                .map(|(count, mines)| (count / most, mines.into_iter().map(|m| m / most).collect::<Vec<_>>()))
+                .collect::<Vec<_>>());
+        }
+
+        match self.mines
+        {
+            None =>
+            {
+                for ((cells, _), dist) in components.iter().zip(solutions.iter())
+                {
+                    let total: f64 = dist.iter().map(|(count, _)| count).sum();
+                    for (i, cell) in cells.iter().enumerate()
+                    {
+                        let mined: f64 = dist.iter().map(|(_, mines)| mines[i]).sum();
+                        result[*cell] = Some(mined / total);
+                    }
+                }
+            }
+            Some(total) =>
+            {
+                let found = known.iter().filter(|k| **k == Some(true)).count();
+                let remaining = total.checked_sub(found).ok_or(SolverError::Inconsistent)?;
+                let counts: Vec<Vec<f64>> = solutions.iter()
+                    .map(|dist| dist.iter().map(|(count, _)| *count).collect())
+                    .collect();
+                let all = counts.iter().fold(vec![1.0], |acc, c| convolve(&acc, c));
+
+                // Placements of the mines left outside the frontier, as logarithms
+                // since they overflow on large boards, then relative to the
+                // heaviest number of mines in the frontier
+                let ln_binomials = ln_binomials(others.len());
+                let ln_outside = |k: usize| (k <= remaining)
+                    .then(|| ln_binomials.get(remaining - k).copied())
+                    .flatten();
+                let heaviest = all.iter()
+                    .enumerate()
+                    .filter(|(_, w)| **w > 0.0)
+                    .filter_map(|(k, w)| Some(w.ln() + ln_outside(k)?))
+                    .fold(f64::NEG_INFINITY, f64::max);
+                if heaviest == f64::NEG_INFINITY
+                {
+                    return Err(SolverError::Inconsistent);
+                }
+                let term = |w: f64, k: usize| match ln_outside(k)
+                {
+                    Some(ln) if w > 0.0 => w * (ln - heaviest).exp(),
+                    _ => 0.0,
+                };
+                let weight: f64 = all.iter().enumerate().map(|(k, w)| term(*w, k)).sum();
+
+                for (i, ((cells, _), dist)) in components.iter().zip(solutions.iter()).enumerate()
+                {
+                    let rest = counts.iter()
+                        .enumerate()
+                        .filter(|(j, _)| *j != i)
+                        .fold(vec![1.0], |acc, (_, c)| convolve(&acc, c));
+                    for (k, (_, mines)) in dist.iter().enumerate()
+                    {
+                        let w: f64 = rest.iter().enumerate().map(|(r, w)| term(*w, k + r)).sum();
+                        for (n, cell) in cells.iter().enumerate()
+                        {
+                            *result[*cell].get_or_insert(0.0) += mines[n] * w / weight;
+                        }
+                    }
+                }
+
+                if !others.is_empty()
+                {
+                    let expected: f64 = all.iter()
+                        .enumerate()
+                        .filter(|(k, _)| *k <= remaining)
+                        .map(|(k, w)| term(*w, k) * (remaining - k) as f64)
+                        .sum();
+                    let p = expected / others.len() as f64 / weight;
+                    for cell in others.iter()
+                    {
+                        result[*cell] = Some(p);
+                    }
+                }
+            }
+        }
+
+        Ok(result.chunks(self.cols).map(|row| row.to_vec()).collect())
+    }
+
+
+    /// The most useful move: a safe cell, else a mine to flag, else the
+    /// safest guess. Nothing if every cell is settled.
+    ///
+    /// When the exact probabilities are [too complex](SolverError::TooComplex)
+    /// the guess is the cell whose numbers are the least crowded, without a
+    /// probability.
+    pub fn hint(&self) -> Result<Option<Hint>, SolverError>
+    {
+        let deductions = self.deduce()?;
+        if let Some(&(row, col)) = deductions.safe.first()
+        {
+            return Ok(Some(Hint::Safe(row, col)));
+        }
+
+        let (probabilities, exact) = match self.probabilities()
+        {
+            Ok(probabilities) => (probabilities, true),
+            Err(SolverError::TooComplex) => (self.estimates()?, false),
+            Err(e) => return Err(e),
+        };
+        let mut best: Option<(usize, usize, Option<f64>)> = None;
+        for (cell, square) in self.squares.iter().enumerate()
+        {
+            let (row, col) = self.position(cell);
+            if *square != Square::Hidden || deductions.mines.contains(&(row, col))
+            {
+                continue;
+            }
+            let p = probabilities[row][col];
+            if p == Some(0.0)
+            {
+                return Ok(Some(Hint::Safe(row, col)));
+            }
+            if p == Some(1.0)
+            {
+                continue;
+            }
+            let better = match (best, p)
+            {
+                (None, _) => true,
+                (Some((_, _, Some(b))), Some(p)) => p < b,
+                // Away from the numbers there is nothing to compare
+                (Some((_, _, None)), Some(_)) => true,
+                (Some(_), None) => false,
+            };
+            if better
+            {
+                best = Some((row, col, p));
+            }
+        }
+
+        if let Some(&(row, col)) = deductions.mines.first()
+        {
+            return Ok(Some(Hint::Mine(row, col)));
+        }
+        let mine = probabilities.iter().flatten().zip(self.squares.iter())
+            .position(|(p, s)| *s == Square::Hidden && *p == Some(1.0));
+        if let Some(cell) = mine
+        {
+            let (row, col) = self.position(cell);
+            return Ok(Some(Hint::Mine(row, col)));
+        }
+        Ok(best.map(|(row, col, probability)| Hint::Guess { row, col, probability: probability.filter(|_| exact) }))
+    }
+
+
+    /// Rough probabilities, in no time: for each cell next to numbers, the
+    /// largest share of mines its numbers still need. Like the exact ones at
+    /// 0 and 1, since a number needing none or all of its cells settles them.
+    fn estimates(&self) -> Result<Vec<Vec<Option<f64>>>, SolverError>
+    {
+        let known = self.initial();
+        let mut result: Vec<Option<f64>> = known.iter().map(|k| k.map(|mine| if mine { 1.0 } else { 0.0 })).collect();
+        for constraint in self.constraints(&known, false)?
+        {
+            let share = constraint.mines as f64 / constraint.cells.len() as f64;
+            for cell in constraint.cells
+            {
+                result[cell] = Some(result[cell].map_or(share, |p| p.max(share)));
+            }
+        }
+        Ok(result.chunks(self.cols).map(|row| row.to_vec()).collect())
+    }
+
+
+    fn position(&self, cell: usize) -> (usize, usize)
+    {
+        (cell / self.cols, cell % self.cols)
+    }
+
+
+    fn neighbours(&self, cell: usize) -> impl Iterator<Item = usize>
+    {
+        let (row, col) = self.position(cell);
+        let (rows, cols) = (self.rows, self.cols);
+        (row.saturating_sub(1)..=(row + 1).min(rows - 1))
+            .flat_map(move |r| (col.saturating_sub(1)..=(col + 1).min(cols - 1)).map(move |c| r * cols + c))
+            .filter(move |n| *n != cell)
+    }
+
+
+    /// What is known of each cell: `Some(true)` for mines, `Some(false)` for free cells.
+    fn initial(&self) -> Vec<Option<bool>>
+    {
+        self.squares.iter()
+            .map(|s| match s
+            {
+                Square::Revealed(_) => Some(false),
+                Square::Mine => Some(true),
+                Square::Hidden => None,
+            })
+            .collect()
+    }
+
+
+    /// The constraints of the numbers on the unknown cells, and optionally
+    /// the one of the total number of mines.
+    fn constraints(&self, known: &[Option<bool>], global: bool) -> Result<Vec<Constraint>, SolverError>
+    {
+        let mut constraints = Vec::new();
+        for (cell, square) in self.squares.iter().enumerate()
+        {
+            let Square::Revealed(n) = *square
+            else
+            {
+                continue;
+            };
+            let n = n as usize;
+            let mut cells = Vec::new();
+            let mut mines = 0;
+            for neighbour in self.neighbours(cell)
+            {
+                match known[neighbour]
+                {
+                    None => cells.push(neighbour),
+                    Some(true) => mines += 1,
+                    Some(false) => {}
+                }
+            }
+            if mines > n || mines + cells.len() < n
+            {
+                return Err(SolverError::Inconsistent);
+            }
+            if !cells.is_empty()
+            {
+                constraints.push(Constraint { cells, mines: n - mines });
+            }
+        }
+
+        if let (true, Some(total)) = (global, self.mines)
+        {
+            let cells: Vec<usize> = (0..known.len()).filter(|c| known[*c].is_none()).collect();
+            let found = known.iter().filter(|k| **k == Some(true)).count();
+            let mines = total.checked_sub(found).ok_or(SolverError::Inconsistent)?;
+            if mines > cells.len()
+            {
+                return Err(SolverError::Inconsistent);
+            }
+            if !cells.is_empty()
+            {
+                constraints.push(Constraint { cells, mines });
+            }
+        }
+        Ok(constraints)
+    }
+}
+
+
+/// Groups of cells linked by shared constraints, with the indices of their constraints.
+fn components(constraints: &[Constraint], len: usize) -> Vec<(Vec<usize>, Vec<usize>)>
+{
+    let mut parent: Vec<usize> = (0..len).collect();
+    fn find(parent: &mut [usize], x: usize) -> usize
+    {
+        let mut root = x;
+        while parent[root] != root
+        {
+            root = parent[root];
+        }
+        parent[x] = root;
+        root
+    }
+
+    for constraint in constraints
+    {
+        let first = find(&mut parent, constraint.cells[0]);
+        for &cell in constraint.cells[1..].iter()
+        {
+            let root = find(&mut parent, cell);
+            parent[root] = first;
+        }
+    }
+
+    let mut groups: Vec<(usize, Vec<usize>, Vec<usize>)> = Vec::new();
+    for (i, constraint) in constraints.iter().enumerate()
+    {
+        let root = find(&mut parent, constraint.cells[0]);
+        let group = match groups.iter().position(|(r, _, _)| *r == root)
+        {
+            Some(g) => g,
+            None =>
+            {
+                groups.push((root, Vec::new(), Vec::new()));
+                groups.len() - 1
+            }
+        };
+        // Cells in the order the constraints reach them, so they close early
+        for &cell in constraint.cells.iter()
+        {
+            if !groups[group].1.contains(&cell)
+            {
+                groups[group].1.push(cell);
+            }
+        }
+        groups[group].2.push(i);
+    }
+    groups.into_iter().map(|(_, cells, indices)| (cells, indices)).collect()
+}
+
+
+/// All placements of mines on `cells` satisfying `constraints`, grouped by
+/// number of mines: how many there are, and how many put a mine in each cell.
+/// Nothing if they take more than `limit` steps to find.
+fn enumerate(cells: &[usize], constraints: &[&Constraint], limit: usize) -> Option<Vec<(f64, Vec<f64>)>>
+{
+    let of_cell: Vec<Vec<usize>> = cells.iter()
+        .map(|cell| (0..constraints.len()).filter(|i| constraints[*i].cells.contains(cell)).collect())
+        .collect();
+    let mut need: Vec<isize> = constraints.iter().map(|c| c.mines as isize).collect();
+    let mut free: Vec<isize> = constraints.iter().map(|c| c.cells.len() as isize).collect();
+    let mut mined = vec![false; cells.len()];
+    let mut dist = vec![(0.0, vec![0.0; cells.len()]); cells.len() + 1];
+    let mut steps = limit;
+
+    /// False once out of steps.
+    fn search(i: usize, of_cell: &[Vec<usize>], need: &mut [isize], free: &mut [isize], mined: &mut [bool],
+              dist: &mut [(f64, Vec<f64>)], steps: &mut usize) -> bool
+    {
+        if *steps == 0
+        {
+            return false;
+        }
+        *steps -= 1;
+        if i == mined.len()
+        {
+            let k = mined.iter().filter(|m| **m).count();
+            dist[k].0 += 1.0;
+            for (n, m) in mined.iter().enumerate()
+            {
+                if *m
+                {
+                    dist[k].1[n] += 1.0;
+                }
+            }
+            return true;
+        }
+
+        for mine in [false, true]
+        {
+            let ok = of_cell[i].iter().all(|c|
+            {
+                let need = need[*c] - mine as isize;
+                need >= 0 && need < free[*c]
+            });
+            if ok
+            {
+                for c in of_cell[i].iter()
+                {
+                    need[*c] -= mine as isize;
+                    free[*c] -= 1;
+                }
+                mined[i] = mine;
+                if !search(i + 1, of_cell, need, free, mined, dist, steps)
+                {
+                    return false;
+                }
+                for c in of_cell[i].iter()
+                {
+                    need[*c] += mine as isize;
+                    free[*c] += 1;
+                }
+            }
+        }
+        mined[i] = false;
+        true
+    }
+
+    search(0, &of_cell, &mut need, &mut free, &mut mined, &mut dist, &mut steps).then_some(dist)
+}
+
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate()
+    {
+        for (j, y) in b.iter().enumerate()
+        {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+
+/// The logarithms of the binomial coefficients of `n` over 0 to `n`.
+fn ln_binomials(n: usize) -> Vec<f64>
+{
+    let mut ln = Vec::with_capacity(n + 1);
+    ln.push(0.0);
+    for k in 0..n
+    {
+        ln.push(ln[k] + ((n - k) as f64).ln() - ((k + 1) as f64).ln());
+    }
+    ln
+}
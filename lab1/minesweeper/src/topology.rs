@@ -0,0 +1,221 @@
+/// How the cells of a board are laid out and which ones touch.
+///
+/// Cells are numbered from 0 in row-major order, layer by layer for boards
+/// with more than two dimensions.
+pub trait Topology
+{
+    /// Number of cells of the board.
+    fn cells(&self) -> usize;
+
+    /// The cells touching `cell`, without `cell` itself and without repetitions.
+    fn neighbours(&self, cell: usize) -> Vec<usize>;
+
+    /// Rows and columns of the board when it is the classic flat one, which
+    /// [`annotate_on`](crate::annotate_on) annotates in a single pass with
+    /// [`crate::bytes`] instead of asking for the neighbours of each cell.
+    fn grid(&self) -> Option<(usize, usize)>
+    {
+        None
+    }
+}
+
+
+/// The classic flat board with 8 neighbours per cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangular
+{
+    pub rows: usize,
+    pub cols: usize,
+}
+
+
+/// A flat board whose edges wrap around, as on a torus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Toroidal
+{
+    pub rows: usize,
+    pub cols: usize,
+}
+
+
+/// A board of hexagons with 6 neighbours per cell, odd rows shifted half a
+/// cell to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hexagonal
+{
+    pub rows: usize,
+    pub cols: usize,
+}
+
+
+/// A cube of `layers` boards stacked on each other, with 26 neighbours per cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cubic
+{
+    pub layers: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+
+impl Rectangular
+{
+    pub fn new(rows: usize, cols: usize) -> Self
+    {
+        Rectangular { rows, cols }
+    }
+}
+
+
+impl Toroidal
+{
+    pub fn new(rows: usize, cols: usize) -> Self
+    {
+        Toroidal { rows, cols }
+    }
+}
+
+
+impl Hexagonal
+{
+    pub fn new(rows: usize, cols: usize) -> Self
+    {
+        Hexagonal { rows, cols }
+    }
+}
+
+
+impl Cubic
+{
+    pub fn new(layers: usize, rows: usize, cols: usize) -> Self
+    {
+        Cubic { layers, rows, cols }
+    }
+}
+
+
+impl Topology for Rectangular
+{
+    fn cells(&self) -> usize
+    {
+        self.rows * self.cols
+    }
+
+
+    fn neighbours(&self, cell: usize) -> Vec<usize>
+    {
+        Cubic::new(1, self.rows, self.cols).neighbours(cell)
+    }
+
+
+    fn grid(&self) -> Option<(usize, usize)>
+    {
+        Some((self.rows, self.cols))
+    }
+}
+
+
+impl Topology for Toroidal
+{
+    fn cells(&self) -> usize
+    {
+        self.rows * self.cols
+    }
+
+
+    fn neighbours(&self, cell: usize) -> Vec<usize>
+    {
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        let mut neighbours = Vec::with_capacity(8);
+        for dr in [self.rows - 1, 0, 1]
+        {
+            for dc in [self.cols - 1, 0, 1]
+            {
+                let n = (row + dr) % self.rows * self.cols + (col + dc) % self.cols;
+                // On narrow boards the same cell is reached from both sides
+                if n != cell && !neighbours.contains(&n)
+                {
+                    neighbours.push(n);
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+
+impl Topology for Hexagonal
+{
+    fn cells(&self) -> usize
+    {
+        self.rows * self.cols
+    }
+
+
+    fn neighbours(&self, cell: usize) -> Vec<usize>
+    {
+        let (row, col) = ((cell / self.cols) as isize, (cell % self.cols) as isize);
+        // The rows above and below are shifted towards odd rows
+        let shift = row % 2;
+        let offsets = [(-1, shift - 1), (-1, shift), (0, -1), (0, 1), (1, shift - 1), (1, shift)];
+
+        offsets.iter()
+            .map(|(dr, dc)| (row + dr, col + dc))
+            .filter(|(r, c)| (0..self.rows as isize).contains(r) && (0..self.cols as isize).contains(c))
+            .map(|(r, c)| r as usize * self.cols + c as usize)
+            .collect()
+    }
+}
+
+
+impl Topology for Cubic
+{
+    fn cells(&self) -> usize
+    {
+        self.layers * self.rows * self.cols
+    }
+
+
+    fn neighbours(&self, cell: usize) -> Vec<usize>
+    {
+        let area = self.rows * self.cols;
+        let (layer, row, col) = (cell / area, cell % area / self.cols, cell % self.cols);
+        let around = |x: usize, len: usize| x.saturating_sub(1)..=(x + 1).min(len - 1);
+
+        let mut neighbours = Vec::with_capacity(26);
+        for l in around(layer, self.layers)
+        {
+            for r in around(row, self.rows)
+            {
+                for c in around(col, self.cols)
+                {
+                    let n = l * area + r * self.cols + c;
+                    if n != cell
+                    {
+                        neighbours.push(n);
+                    }
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+
+/// The number of mines around each cell of `mines`, or `None` for the mines.
+pub fn count_mines<T: Topology + ?Sized>(topology: &T, mines: &[bool]) -> Vec<Option<u32>>
+{
+    (0..topology.cells())
+        .map(|cell|
+        {
+            if mines[cell]
+            {
+                None
+            }
+            else
+            {
+                Some(topology.neighbours(cell).iter().filter(|n| mines[**n]).count() as u32)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use minesweeper::board::{Board, BoardError, Format, MAX_CELLS};
+use minesweeper::try_annotate;
+
+fn sample() -> Board {
+    Board::new(2, 3, &[(0, 1), (1, 2)]).unwrap()
+}
+
+#[test]
+fn text_round_trip() {
+    let board = Board::from_text(".*.\r\n..*\n").unwrap();
+    assert_eq!(board, sample());
+    assert_eq!(board.to_text(), ".*.\n..*\n");
+    assert_eq!(Board::from_text(" * \n  *").unwrap(), sample());
+    assert_eq!(board.annotate(), vec!["1*2", "12*"]);
+}
+
+#[test]
+fn text_errors() {
+    assert_eq!(Board::from_text(""), Err(BoardError::Empty));
+    assert_eq!(
+        Board::from_text("..*\n.*\n"),
+        Err(BoardError::RaggedRow { row: 1, len: 2, expected: 3 })
+    );
+    assert_eq!(
+        Board::from_text("..*\n.x.\n"),
+        Err(BoardError::InvalidCharacter { row: 1, col: 1, ch: 'x' })
+    );
+    assert_eq!(
+        Board::from_text("..*\n.x.\n").unwrap_err().to_string(),
+        "invalid character 'x' at row 2, column 2"
+    );
+}
+
+#[test]
+fn mbf_round_trip() {
+    let data = sample().to_mbf().unwrap();
+    assert_eq!(data, vec![3, 2, 0, 2, 1, 0, 2, 1]);
+    assert_eq!(Board::from_mbf(&data).unwrap(), sample());
+}
+
+#[test]
+fn mbf_errors() {
+    assert_eq!(Board::from_mbf(&[3, 2]), Err(BoardError::Truncated { len: 2, expected: 4 }));
+    assert_eq!(Board::from_mbf(&[3, 2, 0, 2, 1, 0]), Err(BoardError::Truncated { len: 6, expected: 8 }));
+    assert_eq!(Board::from_mbf(&[3, 2, 0, 0, 9]), Err(BoardError::TrailingData { len: 5, expected: 4 }));
+    assert_eq!(Board::from_mbf(&[3, 2, 0, 1, 3, 0]), Err(BoardError::MineOutOfBounds { row: 0, col: 3 }));
+    assert_eq!(Board::from_mbf(&[3, 2, 0, 2, 1, 0, 1, 0]), Err(BoardError::DuplicateMine { row: 0, col: 1 }));
+    assert_eq!(Board::from_mbf(&[0, 2, 0, 0]), Err(BoardError::Empty));
+    assert_eq!(Board::new(1, 256, &[]).unwrap().to_mbf(), Err(BoardError::TooLarge));
+}
+
+#[test]
+fn json_round_trip() {
+    let json = sample().to_json();
+    assert_eq!(json, r#"{"rows":2,"cols":3,"mines":[[0,1],[1,2]]}"#);
+    assert_eq!(Board::from_json(&json).unwrap(), sample());
+}
+
+#[test]
+fn json_errors() {
+    assert!(matches!(Board::from_json(r#"{"rows":2}"#), Err(BoardError::Json(_))));
+    assert_eq!(
+        Board::from_json(r#"{"rows":2,"cols":3,"mines":[[2,0]]}"#),
+        Err(BoardError::MineOutOfBounds { row: 2, col: 0 })
+    );
+}
+
+#[test]
+fn formats() {
+    assert_eq!(Format::from_path(Path::new("board.MBF")), Format::Mbf);
+    assert_eq!(Format::from_path(Path::new("board.json")), Format::Json);
+    assert_eq!(Format::from_path(Path::new("board.txt")), Format::Text);
+    assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+    for format in [Format::Text, Format::Mbf, Format::Json] {
+        let data = sample().serialize(format).unwrap();
+        assert_eq!(Board::parse(&data, format).unwrap(), sample());
+    }
+}
+
+#[test]
+fn try_annotate_reports_bad_boards() {
+    assert_eq!(try_annotate(&["* ", " *"]).unwrap(), vec!["*2", "2*"]);
+    assert_eq!(try_annotate(&["* ", "*"]), Err(BoardError::RaggedRow { row: 1, len: 1, expected: 2 }));
+    assert_eq!(try_annotate(&["*1"]), Err(BoardError::InvalidCharacter { row: 0, col: 1, ch: '1' }));
+}
+
+#[test]
+#[should_panic(expected = "row 2 has 1 cells, expected 2")]
+fn annotate_panics_with_a_description() {
+    minesweeper::annotate(&["  ", " "]);
+}
+
+#[test]
+fn sizes_are_checked_before_allocating() {
+    let huge = Board::from_json(r#"{"rows":100000000000,"cols":100000000000,"mines":[]}"#).unwrap_err();
+    assert_eq!(huge, BoardError::TooManyCells { rows: 100_000_000_000, cols: 100_000_000_000 });
+    assert_eq!(huge.to_string(), "a board of 100000000000 x 100000000000 cells is over the limit of 16777216 cells");
+    assert_eq!(Board::new(usize::MAX, 2, &[]), Err(BoardError::TooManyCells { rows: usize::MAX, cols: 2 }));
+    assert!(Board::new(1, MAX_CELLS, &[]).is_ok());
+    assert_eq!(Board::from_mbf(&[1, 1, 0, 2, 0, 0, 0, 0]), Err(BoardError::TooManyMines { mines: 2, cells: 1 }));
+}
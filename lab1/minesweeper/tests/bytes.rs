@@ -0,0 +1,96 @@
+use minesweeper::board::BoardError;
+use minesweeper::bytes;
+use minesweeper::topology::{count_mines, Rectangular};
+
+/// A board with about a fifth of the cells mined, the same for the same seed.
+fn random_cells(rows: usize, cols: usize, mut seed: u64) -> Vec<u8> {
+    (0..rows * cols)
+        .map(|_| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            if (seed >> 60) < 3 { b'*' } else { b' ' }
+        })
+        .collect()
+}
+
+/// The counts of the topology engine, written the same way.
+fn expected(cells: &[u8], rows: usize, cols: usize) -> Vec<u8> {
+    let mines: Vec<bool> = cells.iter().map(|b| *b == b'*').collect();
+    count_mines(&Rectangular::new(rows, cols), &mines)
+        .iter()
+        .map(|count| match count {
+            None => b'*',
+            Some(0) => b' ',
+            Some(n) => b'0' + *n as u8,
+        })
+        .collect()
+}
+
+#[test]
+fn matches_the_topology_engine() {
+    for (rows, cols) in [(1, 1), (1, 7), (7, 1), (2, 2), (13, 17), (150, 9)] {
+        let cells = random_cells(rows, cols, (rows * cols) as u64);
+        assert_eq!(bytes::annotate(&cells, cols).unwrap(), expected(&cells, rows, cols), "{} x {}", rows, cols);
+    }
+}
+
+#[test]
+fn same_layout_as_the_input() {
+    let cells = b" *  *   *   ";
+    assert_eq!(bytes::annotate(cells, 4).unwrap(), b"2*1 *31 *2  ");
+    assert_eq!(bytes::annotate(cells, 6).unwrap(), b"1*22*112*211");
+}
+
+#[test]
+fn every_cell_mined() {
+    assert_eq!(bytes::annotate(b"******", 3).unwrap(), b"******");
+}
+
+#[test]
+fn empty_board() {
+    assert_eq!(bytes::annotate(b"", 0).unwrap(), b"");
+    assert_eq!(bytes::annotate(b"", 5).unwrap(), b"");
+}
+
+#[test]
+fn incomplete_last_row() {
+    assert_eq!(
+        bytes::annotate(b"* * *", 2),
+        Err(BoardError::SizeMismatch { cells: 5, expected: 6 })
+    );
+    assert_eq!(
+        bytes::annotate(b"*", 0),
+        Err(BoardError::SizeMismatch { cells: 1, expected: 0 })
+    );
+}
+
+#[test]
+fn first_invalid_byte_is_reported() {
+    assert_eq!(
+        bytes::annotate(b"*  \n *x  ", 3),
+        Err(BoardError::InvalidCharacter { row: 1, col: 0, ch: '\n' })
+    );
+}
+
+#[test]
+fn non_ascii_rows_are_invalid() {
+    assert_eq!(
+        minesweeper::try_annotate(&["* é", "   "]),
+        Err(BoardError::InvalidCharacter { row: 0, col: 2, ch: 'é' })
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_bands_agree() {
+    let (rows, cols) = (1000, 37);
+    let mut cells = random_cells(rows, cols, 7);
+    assert_eq!(bytes::par_annotate(&cells, cols), bytes::annotate(&cells, cols));
+
+    // Errors in two bands: the first one wins
+    cells[900 * cols + 3] = b'x';
+    cells[130 * cols + 5] = b'?';
+    assert_eq!(
+        bytes::par_annotate(&cells, cols),
+        Err(BoardError::InvalidCharacter { row: 130, col: 5, ch: '?' })
+    );
+}
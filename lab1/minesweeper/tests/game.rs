@@ -0,0 +1,111 @@
+use minesweeper::game::{Cell, Difficulty, Game, GameError, GameState};
+
+#[test]
+fn rejects_invalid_boards() {
+    assert_eq!(Game::new(0, 5, 1, 0).unwrap_err(), GameError::EmptyBoard);
+    assert_eq!(Game::new(3, 3, 9, 0).unwrap_err(), GameError::TooManyMines);
+    assert_eq!(Game::from_minefield(&[]).unwrap_err(), GameError::EmptyBoard);
+
+    let ragged = Game::from_minefield(&["* ", "  ", " "]).unwrap_err();
+    assert_eq!(ragged, GameError::RaggedRow { row: 2, len: 1, expected: 2 });
+    assert_eq!(ragged.to_string(), "row 3 has 1 cells, expected 2");
+}
+
+#[test]
+fn first_reveal_is_safe_and_opens_an_area() {
+    for seed in 0..50 {
+        let mut game = Game::new(9, 9, 10, seed).unwrap();
+        assert!(!game.is_placed());
+        let opened = game.reveal(4, 4).unwrap();
+        assert_eq!(game.state(), GameState::Playing);
+        assert!(!game.is_mine(4, 4));
+        assert_eq!(game.adjacent_mines(4, 4), 0);
+        assert!(opened.len() >= 9);
+        assert!(game.neighbours(4, 4).all(|(r, c)| game.cell(r, c) == Cell::Revealed));
+    }
+}
+
+#[test]
+fn crowded_board_only_spares_the_clicked_cell() {
+    let mut game = Game::new(3, 3, 8, 7).unwrap();
+    assert_eq!(game.reveal(0, 0).unwrap(), vec![(0, 0)]);
+    assert_eq!(game.adjacent_mines(0, 0), 3);
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn same_seed_same_board() {
+    let mut a = Game::new(16, 16, 40, 42).unwrap();
+    let mut b = Game::new(16, 16, 40, 42).unwrap();
+    a.reveal(0, 0).unwrap();
+    b.reveal(0, 0).unwrap();
+    assert_eq!(a.annotated(), b.annotated());
+}
+
+#[test]
+fn flood_fill_stops_at_numbers() {
+    #[rustfmt::skip]
+    let mut game = Game::from_minefield(&[
+        "    ",
+        "    ",
+        "   *",
+    ]).unwrap();
+    let opened = game.reveal(0, 0).unwrap();
+    assert_eq!(opened.len(), 11);
+    assert_eq!(game.cell(2, 3), Cell::Hidden);
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn revealing_a_mine_loses() {
+    let mut game = Game::from_minefield(&["* ", "  "]).unwrap();
+    assert_eq!(game.reveal(0, 0).unwrap(), vec![(0, 0)]);
+    assert_eq!(game.state(), GameState::Lost);
+    assert_eq!(game.reveal(1, 1).unwrap_err(), GameError::GameOver);
+}
+
+#[test]
+fn flags() {
+    let mut game = Game::from_minefield(&["* ", "  "]).unwrap();
+    assert_eq!(game.mines_left(), 1);
+    assert!(game.toggle_flag(0, 0).unwrap());
+    assert_eq!(game.mines_left(), 0);
+    assert_eq!(game.reveal(0, 0).unwrap_err(), GameError::Flagged);
+    assert!(!game.toggle_flag(0, 0).unwrap());
+    assert_eq!(game.cell(0, 0), Cell::Hidden);
+    assert_eq!(game.toggle_flag(2, 0).unwrap_err(), GameError::OutOfBounds);
+}
+
+#[test]
+fn chording() {
+    #[rustfmt::skip]
+    let mut game = Game::from_minefield(&[
+        "*  ",
+        "   ",
+        "  *",
+    ]).unwrap();
+    game.reveal(1, 1).unwrap();
+    // Not enough flags yet
+    assert!(game.chord(1, 1).unwrap().is_empty());
+    game.toggle_flag(0, 0).unwrap();
+    game.toggle_flag(2, 2).unwrap();
+    assert_eq!(game.chord(1, 1).unwrap().len(), 6);
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn chording_on_a_wrong_flag_loses() {
+    let mut game = Game::from_minefield(&["* ", "  "]).unwrap();
+    game.reveal(1, 1).unwrap();
+    game.toggle_flag(0, 1).unwrap();
+    game.chord(1, 1).unwrap();
+    assert_eq!(game.state(), GameState::Lost);
+}
+
+#[test]
+fn difficulty_presets() {
+    let game = Game::with_difficulty("Expert".parse().unwrap(), 0);
+    assert_eq!((game.rows(), game.cols(), game.mines()), (16, 30, 99));
+    assert_eq!("beginner".parse::<Difficulty>().unwrap().size(), (9, 9, 10));
+    assert!("hard".parse::<Difficulty>().is_err());
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use minesweeper::game::{Game, GameError, GameState};
+use minesweeper::generator::{solve, Generator, GeneratorError};
+
+#[test]
+fn generated_boards_need_no_guess() {
+    for seed in 0..5 {
+        let board = Generator::new(16, 16, 40).unwrap().seed(seed).generate().unwrap();
+        assert_eq!(board.minefield.len(), 16);
+        assert_eq!(board.minefield.iter().flat_map(|row| row.chars()).filter(|c| *c == '*').count(), 40);
+
+        let mut game = board.game();
+        assert_eq!(game.state(), GameState::Playing);
+        assert!(solve(&mut game));
+        assert_eq!(game.state(), GameState::Won);
+    }
+}
+
+#[test]
+fn same_seed_same_board() {
+    let a = Generator::new(9, 9, 10).unwrap().seed(7).generate().unwrap();
+    let b = Generator::new(9, 9, 10).unwrap().seed(7).generate().unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn first_click_and_density() {
+    let board = Generator::with_density(10, 10, 0.15).unwrap()
+        .first_click(0, 0).unwrap()
+        .seed(3)
+        .generate()
+        .unwrap();
+    assert_eq!(board.first_click, (0, 0));
+    assert_eq!(board.minefield.iter().flat_map(|row| row.chars()).filter(|c| *c == '*').count(), 15);
+    assert_eq!(&board.minefield[0][..2], "  ");
+}
+
+#[test]
+fn guessing_boards_are_not_solved() {
+    // The 1 touches the three other cells, one of which is the mine
+    let mut game = Game::from_minefield(&["* ", "  "]).unwrap();
+    game.reveal(1, 1).unwrap();
+    assert!(!solve(&mut game));
+}
+
+#[test]
+fn hopeless_boards_time_out() {
+    let err = Generator::new(5, 5, 20).unwrap()
+        .time_budget(Duration::ZERO)
+        .generate()
+        .unwrap_err();
+    assert_eq!(err, GeneratorError::TimedOut { attempts: 1 });
+}
+
+#[test]
+fn invalid_settings() {
+    assert_eq!(Generator::new(3, 3, 9).unwrap_err(), GeneratorError::Game(GameError::TooManyMines));
+    assert_eq!(Generator::with_density(9, 9, 1.5).unwrap_err(), GeneratorError::InvalidDensity);
+    assert!(Generator::new(9, 9, 10).unwrap().first_click(9, 0).is_err());
+}
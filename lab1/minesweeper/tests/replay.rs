@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use minesweeper::board::BoardError;
+use minesweeper::game::{Cell, Game, GameError, GameState};
+use minesweeper::replay::{three_bv, Action, Move, Replay, ReplayError};
+
+#[rustfmt::skip]
+const FIELD: [&str; 4] = [
+    " *  ",
+    "    ",
+    "    ",
+    "   *",
+];
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+/// Play `moves` on a new game of `FIELD`, keeping only the accepted ones.
+fn record(moves: &[(Action, usize, usize, u64)]) -> (Game, Vec<Move>) {
+    let mut game = Game::from_minefield(&FIELD).unwrap();
+    let moves: Vec<Move> = moves
+        .iter()
+        .map(|(action, row, col, time)| Move::new(*action, *row, *col, ms(*time)))
+        .filter(|m| m.apply(&mut game).is_ok())
+        .collect();
+    (game, moves)
+}
+
+#[test]
+fn three_bv_of_the_classic_example() {
+    #[rustfmt::skip]
+    let annotated = [
+        "1*3*1",
+        "13*31",
+        " 2*2 ",
+        " 111 ",
+    ];
+    // Two openings and the four numbers not touching them
+    assert_eq!(three_bv(&annotated), 6);
+    assert_eq!(three_bv(&["   ", "   "]), 1);
+    assert_eq!(three_bv(&["1*1"]), 2);
+    assert_eq!(three_bv(&["***"]), 0);
+}
+
+#[test]
+fn round_trip_through_bytes() {
+    let (game, moves) = record(&[
+        (Action::Flag, 0, 1, 0),
+        (Action::Reveal, 2, 0, 850),
+        (Action::Chord, 1, 1, 1200),
+        (Action::Reveal, 0, 3, 70_000),
+    ]);
+    let replay = Replay::new(&game, moves).unwrap();
+    let data = replay.to_bytes().unwrap();
+    // Header, board with 2 mines, then 3 or 4 bytes per move
+    assert_eq!(data.len(), 5 + 8 + 3 + 5 + 3 + 5);
+    assert_eq!(Replay::from_bytes(&data).unwrap(), replay);
+}
+
+#[test]
+fn playback_reaches_the_same_game() {
+    let (game, moves) = record(&[
+        (Action::Reveal, 2, 0, 0),
+        (Action::Flag, 3, 3, 400),
+        (Action::Reveal, 0, 0, 900),
+        (Action::Reveal, 0, 2, 1300),
+        (Action::Reveal, 0, 3, 1500),
+    ]);
+    assert_eq!(game.state(), GameState::Won);
+
+    let replay = Replay::from_bytes(&Replay::new(&game, moves).unwrap().to_bytes().unwrap()).unwrap();
+    let played = replay.play().unwrap();
+    assert_eq!(played.state(), GameState::Won);
+    assert_eq!(played.annotated(), game.annotated());
+    assert_eq!(played.cell(3, 3), Cell::Flagged);
+}
+
+#[test]
+fn statistics_of_a_won_game() {
+    let (game, moves) = record(&[
+        (Action::Reveal, 2, 0, 0),
+        (Action::Flag, 3, 3, 400),
+        (Action::Reveal, 0, 0, 900),
+        (Action::Reveal, 0, 2, 1300),
+        (Action::Reveal, 0, 3, 2000),
+    ]);
+    let stats = Replay::new(&game, moves).unwrap().stats().unwrap();
+    // Two openings and the 1 in the top left corner
+    assert_eq!(stats.bbbv, 3);
+    assert_eq!(stats.solved_bbbv, 3);
+    assert_eq!(stats.clicks, 5);
+    assert_eq!(stats.time, ms(2000));
+    assert_eq!(stats.state, GameState::Won);
+    assert_eq!(stats.efficiency(), 0.6);
+    assert_eq!(stats.bbbv_per_second(), 1.5);
+}
+
+#[test]
+fn statistics_of_a_lost_game() {
+    let (game, moves) = record(&[(Action::Reveal, 2, 0, 0), (Action::Reveal, 0, 1, 500)]);
+    let stats = Replay::new(&game, moves).unwrap().stats().unwrap();
+    assert_eq!(stats.state, GameState::Lost);
+    assert_eq!((stats.solved_bbbv, stats.bbbv), (1, 3));
+    assert_eq!(stats.efficiency(), 0.5);
+}
+
+#[test]
+fn no_replay_before_the_mines_are_placed() {
+    let game = Game::new(9, 9, 10, 0).unwrap();
+    assert_eq!(Replay::new(&game, Vec::new()), Err(ReplayError::NotStarted));
+}
+
+#[test]
+fn invalid_files() {
+    let (game, moves) = record(&[(Action::Reveal, 2, 0, 0), (Action::Reveal, 0, 0, 300)]);
+    let data = Replay::new(&game, moves).unwrap().to_bytes().unwrap();
+
+    assert_eq!(Replay::from_bytes(b"MSR"), Err(ReplayError::NotAReplay));
+    assert_eq!(Replay::from_bytes(b"MBF\x01\x04\x04"), Err(ReplayError::NotAReplay));
+    let mut version = data.clone();
+    version[4] = 9;
+    assert_eq!(Replay::from_bytes(&version), Err(ReplayError::UnsupportedVersion(9)));
+    assert_eq!(Replay::from_bytes(&data[..10]), Err(ReplayError::Truncated));
+    assert_eq!(Replay::from_bytes(&data[..data.len() - 1]), Err(ReplayError::Truncated));
+
+    let mut board = data.clone();
+    board[9] = 7;
+    assert_eq!(
+        Replay::from_bytes(&board),
+        Err(ReplayError::Board(BoardError::MineOutOfBounds { row: 0, col: 7 }))
+    );
+
+    let mut action = data.clone();
+    action.extend([3, 0, 0]);
+    assert_eq!(Replay::from_bytes(&action), Err(ReplayError::InvalidMove { offset: data.len() }));
+}
+
+#[test]
+fn illegal_moves_are_reported() {
+    let (game, mut moves) = record(&[(Action::Reveal, 2, 0, 0)]);
+    moves.push(Move::new(Action::Reveal, 7, 0, ms(10)));
+    let replay = Replay::from_bytes(&Replay::new(&game, moves).unwrap().to_bytes().unwrap()).unwrap();
+    assert_eq!(
+        replay.play().unwrap_err(),
+        ReplayError::IllegalMove { index: 1, error: GameError::OutOfBounds }
+    );
+}
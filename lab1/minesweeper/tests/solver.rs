@@ -0,0 +1,134 @@
+use minesweeper::solver::{Hint, Solver, SolverError};
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("no probability");
+    assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+}
+
+#[test]
+fn single_unknown_neighbour_is_a_mine() {
+    let solver = Solver::new(&["?1", "11"]).unwrap();
+    let deductions = solver.deduce().unwrap();
+    assert_eq!(deductions.mines, vec![(0, 0)]);
+    assert!(deductions.safe.is_empty());
+    assert_eq!(solver.hint().unwrap(), Some(Hint::Mine(0, 0)));
+}
+
+#[test]
+fn one_two_one_needs_subset_reasoning() {
+    #[rustfmt::skip]
+    let solver = Solver::new(&[
+        "???",
+        "121",
+        "   ",
+    ]).unwrap();
+    let deductions = solver.deduce().unwrap();
+    assert_eq!(deductions.mines, vec![(0, 0), (0, 2)]);
+    assert_eq!(deductions.safe, vec![(0, 1)]);
+    assert_eq!(solver.hint().unwrap(), Some(Hint::Safe(0, 1)));
+}
+
+#[test]
+fn known_mines_are_subtracted() {
+    let solver = Solver::new(&["*1?", "11?"]).unwrap();
+    assert_eq!(solver.deduce().unwrap().safe, vec![(0, 2), (1, 2)]);
+}
+
+#[test]
+fn fifty_fifty() {
+    let solver = Solver::new(&["??", "11"]).unwrap();
+    assert_eq!(solver.deduce().unwrap(), Default::default());
+    let p = solver.probabilities().unwrap();
+    assert_close(p[0][0], 0.5);
+    assert_close(p[0][1], 0.5);
+    assert_close(p[1][0], 0.0);
+    assert_eq!(solver.hint().unwrap(), Some(Hint::Guess { row: 0, col: 0, probability: Some(0.5) }));
+}
+
+#[test]
+fn mine_count_settles_the_board() {
+    let solver = Solver::new(&["?1??"]).unwrap().with_mines(2);
+    assert_eq!(solver.deduce().unwrap().mines, vec![(0, 3)]);
+    let solver = Solver::new(&["?1??"]).unwrap().with_mines(1);
+    assert_eq!(solver.deduce().unwrap().safe, vec![(0, 3)]);
+}
+
+#[test]
+fn probabilities_weigh_the_cells_away_from_the_numbers() {
+    let p = Solver::new(&["?1??"]).unwrap().with_mines(1).probabilities().unwrap();
+    assert_close(p[0][0], 0.5);
+    assert_close(p[0][3], 0.0);
+    let p = Solver::new(&["?1??"]).unwrap().with_mines(2).probabilities().unwrap();
+    assert_close(p[0][2], 0.5);
+    assert_close(p[0][3], 1.0);
+    assert_eq!(Solver::new(&["?1??"]).unwrap().probabilities().unwrap()[0][3], None);
+    assert_eq!(Solver::new(&["?1??"]).unwrap().with_mines(1).hint().unwrap(), Some(Hint::Safe(0, 3)));
+}
+
+#[test]
+fn independent_frontiers_share_the_mine_count() {
+    let p = Solver::new(&["?1??1??"]).unwrap().with_mines(2).probabilities().unwrap();
+    assert_close(p[0][0], 0.5);
+    assert_close(p[0][5], 0.5);
+    assert_close(p[0][6], 0.0);
+    let p = Solver::new(&["?1??1??"]).unwrap().with_mines(3).probabilities().unwrap();
+    assert_close(p[0][3], 0.5);
+    assert_close(p[0][6], 1.0);
+}
+
+#[test]
+fn fully_hidden_board() {
+    let solver = Solver::new(&["???", "???", "???"]).unwrap();
+    assert_eq!(solver.hint().unwrap(), Some(Hint::Guess { row: 0, col: 0, probability: None }));
+    let p = solver.with_mines(3).probabilities().unwrap();
+    assert_close(p[1][1], 1.0 / 3.0);
+}
+
+#[test]
+fn nothing_left() {
+    assert_eq!(Solver::new(&["*1"]).unwrap().hint().unwrap(), None);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Solver::new(&[]).unwrap_err(), SolverError::EmptyBoard);
+    assert_eq!(Solver::new(&["??", "?"]).unwrap_err(), SolverError::RaggedRow { row: 1 });
+    assert_eq!(Solver::new(&["?x"]).unwrap_err(), SolverError::InvalidCharacter { row: 0, col: 1, ch: 'x' });
+    assert_eq!(Solver::new(&["?2", "  "]).unwrap().deduce().unwrap_err(), SolverError::Inconsistent);
+    assert_eq!(Solver::new(&["?1?"]).unwrap().with_mines(3).probabilities().unwrap_err(), SolverError::Inconsistent);
+}
+
+#[test]
+fn large_frontiers_give_up_counting() {
+    // Every 5 needs 3 of the 6 cells around it, sharing two with the next one
+    let hidden = "?".repeat(101);
+    let numbers: String = (0..101).map(|c| if c % 2 == 0 { '*' } else { '5' }).collect();
+    let solver = Solver::new(&[&hidden, &numbers, &hidden]).unwrap().with_step_limit(50_000);
+    assert_eq!(solver.probabilities().unwrap_err(), SolverError::TooComplex);
+    match solver.hint().unwrap() {
+        Some(Hint::Guess { probability, .. }) => assert_eq!(probability, None),
+        hint => panic!("expected a guess, got {:?}", hint),
+    }
+}
+
+#[test]
+fn large_boards_weigh_the_mine_count() {
+    // C(10000, 2000) is far beyond f64
+    let hidden = "?".repeat(100);
+    let mut rows = vec![hidden.as_str(); 100];
+    let p = Solver::new(&rows).unwrap().with_mines(2000).probabilities().unwrap();
+    assert_close(p[0][0], 0.2);
+    assert_close(p[99][99], 0.2);
+
+    let first = format!("1{}", "?".repeat(99));
+    rows[0] = &first;
+    let solver = Solver::new(&rows).unwrap().with_mines(2000);
+    let p = solver.probabilities().unwrap();
+    assert_close(p[0][1], 1.0 / 3.0);
+    assert_close(p[1][1], 1.0 / 3.0);
+    assert_close(p[50][50], 1999.0 / 9996.0);
+    match solver.hint().unwrap() {
+        Some(Hint::Guess { probability: Some(p), .. }) => assert!((p - 1999.0 / 9996.0).abs() < 1e-9),
+        hint => panic!("expected a guess, got {:?}", hint),
+    }
+}
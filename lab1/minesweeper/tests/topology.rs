@@ -0,0 +1,134 @@
+use minesweeper::annotate_on;
+use minesweeper::board::BoardError;
+use minesweeper::topology::{count_mines, Cubic, Hexagonal, Rectangular, Toroidal, Topology};
+
+fn sorted(mut cells: Vec<usize>) -> Vec<usize> {
+    cells.sort();
+    cells
+}
+
+#[test]
+fn rectangular_is_the_classic_board() {
+    let board = Rectangular::new(3, 3);
+    assert_eq!(board.cells(), 9);
+    assert_eq!(sorted(board.neighbours(0)), vec![1, 3, 4]);
+    assert_eq!(board.neighbours(4).len(), 8);
+    #[rustfmt::skip]
+    let field = [
+        " *  ",
+        "   *",
+    ];
+    assert_eq!(annotate_on(&Rectangular::new(2, 4), &field).unwrap(), minesweeper::annotate(&field));
+}
+
+#[test]
+fn toroidal_wraps_around() {
+    let board = Toroidal::new(4, 5);
+    assert_eq!(sorted(board.neighbours(0)), vec![1, 4, 5, 6, 9, 15, 16, 19]);
+    // Narrow boards reach the same cell from both sides only once
+    assert_eq!(sorted(Toroidal::new(1, 3).neighbours(0)), vec![1, 2]);
+    assert_eq!(Toroidal::new(2, 2).neighbours(0).len(), 3);
+    #[rustfmt::skip]
+    assert_eq!(annotate_on(&Toroidal::new(3, 4), &[
+        "*   ",
+        "    ",
+        "    ",
+    ]).unwrap(), vec![
+        "*1 1",
+        "11 1",
+        "11 1",
+    ]);
+}
+
+#[test]
+fn hexagonal_has_six_neighbours() {
+    let board = Hexagonal::new(3, 3);
+    // Even rows reach to the left of the rows around them, odd rows to the right
+    assert_eq!(sorted(board.neighbours(4)), vec![1, 2, 3, 5, 7, 8]);
+    assert_eq!(sorted(board.neighbours(1)), vec![0, 2, 3, 4]);
+    assert_eq!(sorted(board.neighbours(3)), vec![0, 1, 4, 6, 7]);
+    #[rustfmt::skip]
+    assert_eq!(annotate_on(&board, &[
+        "*  ",
+        " * ",
+        "   ",
+    ]).unwrap(), vec![
+        "*21",
+        "2*1",
+        " 11",
+    ]);
+}
+
+#[test]
+fn cubic_has_twenty_six_neighbours() {
+    let cube = Cubic::new(3, 3, 3);
+    assert_eq!(cube.cells(), 27);
+    assert_eq!(cube.neighbours(13).len(), 26);
+    assert_eq!(cube.neighbours(0).len(), 7);
+
+    let mut mines = vec![true; 27];
+    mines[13] = false;
+    assert_eq!(count_mines(&cube, &mines)[13], Some(26));
+    assert_eq!(count_mines(&cube, &mines)[0], None);
+}
+
+#[test]
+fn large_counts_are_letters() {
+    let layer = ["***", "***", "***"];
+    let middle = ["***", "* *", "***"];
+    let field: Vec<&str> = layer.iter().chain(middle.iter()).chain(layer.iter()).copied().collect();
+    let annotated = annotate_on(&Cubic::new(3, 3, 3), &field).unwrap();
+    assert_eq!(annotated[4], "*q*");
+}
+
+#[test]
+fn size_must_match() {
+    assert_eq!(
+        annotate_on(&Hexagonal::new(2, 2), &["  ", "  ", " "]),
+        Err(BoardError::SizeMismatch { cells: 5, expected: 4 })
+    );
+    assert_eq!(
+        annotate_on(&Toroidal::new(1, 2), &[" ."]),
+        Err(BoardError::InvalidCharacter { row: 0, col: 1, ch: '.' })
+    );
+}
+
+/// The classic board seen only through its neighbours, like any other topology.
+struct Plain(Rectangular);
+
+impl Topology for Plain {
+    fn cells(&self) -> usize {
+        self.0.cells()
+    }
+
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        self.0.neighbours(cell)
+    }
+}
+
+#[test]
+fn single_pass_matches_the_neighbours() {
+    let mut seed: u64 = 11;
+    for (rows, cols) in [(1, 1), (1, 9), (9, 1), (3, 3), (17, 23), (70, 5)] {
+        let field: Vec<String> = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| {
+                        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                        if (seed >> 61) < 2 { '*' } else { ' ' }
+                    })
+                    .collect()
+            })
+            .collect();
+        let field: Vec<&str> = field.iter().map(|row| row.as_str()).collect();
+        let board = Rectangular::new(rows, cols);
+        assert_eq!(board.grid(), Some((rows, cols)));
+        assert_eq!(
+            annotate_on(&board, &field).unwrap(),
+            annotate_on(&Plain(board), &field).unwrap(),
+            "{} x {}",
+            rows,
+            cols
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Dumps of decoded records for analysis: JSON lines, or CSV with one
+//! table for each kind of record.
+
+use std::io::{self, Write};
+
+use crate::{CData, Content};
+
+
+/// Write `record` as one line of JSON. Floats that are not finite become `null`.
+pub fn write_json_line(out: &mut impl Write, record: &CData) -> io::Result<()>
+{
+    serde_json::to_writer(&mut *out, record)?;
+    writeln!(out)
+}
+
+
+/// CSV tables of values, multiple values and messages, filled as the
+/// records come.
+pub struct CsvTables<W: Write>
+{
+    values: W,
+    mvalues: W,
+    messages: W,
+}
+
+
+impl<W: Write> CsvTables<W>
+{
+    /// Tables written to the three outputs, starting with their headers.
+    pub fn new(mut values: W, mut mvalues: W, mut messages: W) -> io::Result<Self>
+    {
+        writeln!(values, "type,val,timestamp")?;
+        let vals: Vec<String> = (0..10).map(|j| format!("val{}", j)).collect();
+        writeln!(mvalues, "type,{},timestamp", vals.join(","))?;
+        writeln!(messages, "type,message")?;
+        Ok(CsvTables { values, mvalues, messages })
+    }
+
+
+    /// Add `record` to the table of its kind.
+    pub fn write(&mut self, record: &CData) -> io::Result<()>
+    {
+        match record.content()
+        {
+            Content::ValueStruct { type_, val, timestamp } => writeln!(self.values, "{},{},{}", type_, val, timestamp),
+            Content::MValueStruct { type_, vals, timestamp } =>
+            {
+                let vals: Vec<String> = vals.iter().map(|val| val.to_string()).collect();
+                writeln!(self.mvalues, "{},{},{}", type_, vals.join(","), timestamp)
+            }
+            Content::MessageStruct { type_, .. } =>
+                writeln!(self.messages, "{},{}", type_, quote(&record.content().text().unwrap_or_default())),
+        }
+    }
+
+
+    /// Flush the tables and give back the outputs.
+    pub fn finish(mut self) -> io::Result<(W, W, W)>
+    {
+        self.values.flush()?;
+        self.mvalues.flush()?;
+        self.messages.flush()?;
+        Ok((self.values, self.mvalues, self.messages))
+    }
+}
+
+
+/// A CSV field, quoted when it needs to be.
+fn quote(field: &str) -> String
+{
+    if field.contains([',', '"', '\n', '\r'])
+    {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    }
+    else
+    {
+        field.to_string()
+    }
+}
@@ -1,113 +1,595 @@
+use std::fmt;
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
-use std::process::exit;
+use std::io::{self, prelude::*, BufReader};
+use std::sync::OnceLock;
 
+use serde::{Deserialize, Serialize};
 
-enum Content
+use schema::{Layout, Record, Scalar, Schema, Value};
+
+pub mod export;
+pub mod schema;
+
+
+/// Tags of the records, in the first byte of each.
+const VALUE: u8 = 0;
+const MVALUE: u8 = 1;
+const MESSAGE: u8 = 2;
+
+/// Characters of a message, which leave a message record 88 bytes long as in
+/// the original format. Shorter messages are padded with NULs.
+const MESSAGE_LEN: usize = 20;
+
+/// Every record starts at a multiple of this from the first one.
+const RECORD_ALIGN: usize = 4;
+
+
+/// The C struct inside a record. With serde the variant goes in a `kind`
+/// field, and messages are strings without the NULs padding them.
+// Named after the C structs
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum Content
 {
     ValueStruct
     {
-        _type: i32,
+        #[serde(rename = "type")]
+        type_: i32,
         val: f32,
         timestamp: i64,
     },
     MValueStruct
     {
-        _type: i32,
+        #[serde(rename = "type")]
+        type_: i32,
         vals: [f32; 10],
         timestamp: i64,
     },
     MessageStruct
     {
-        _type: i32,
-        message: [char; 21],
-    }, 
+        #[serde(rename = "type")]
+        type_: i32,
+        /// Padded with NULs; a full message has none.
+        #[serde(with = "message_text")]
+        message: [char; MESSAGE_LEN],
+    },
 }
 
+/// A record: its tag and the struct it announces, which always agree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Content", into = "Content")]
 pub struct CData
 {
-    _type: u8,
+    tag: u8,
     content: Content,
 }
 
+
+/// What can go wrong while decoding records; offsets are in bytes from the
+/// start of the data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError
+{
+    /// The record at `offset` needs `needed` bytes but only `available` are left.
+    Truncated { offset: usize, needed: usize, available: usize },
+    UnknownTag { offset: usize, tag: u8 },
+    /// Character `index` of the message at `offset` is not a valid code point.
+    InvalidChar { offset: usize, index: usize, code: u32 },
+    /// `len` bytes are left at `offset` after the last record.
+    TrailingGarbage { offset: usize, len: usize },
+    /// The values of the record at `offset` do not fit the struct of `tag`.
+    Mismatch { offset: usize, tag: u8 },
+    Io(io::ErrorKind),
+}
+
+
+impl fmt::Display for DecodeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            DecodeError::Truncated { offset, needed, available } =>
+                write!(f, "record at byte {} is truncated: {} bytes needed, {} available", offset, needed, available),
+            DecodeError::UnknownTag { offset, tag } => write!(f, "unknown record tag {} at byte {}", tag, offset),
+            DecodeError::InvalidChar { offset, index, code } =>
+                write!(f, "character {} of the message at byte {} is not valid: {:#x}", index, offset, code),
+            DecodeError::TrailingGarbage { offset, len } =>
+                write!(f, "{} unexpected bytes after the last record at byte {}", len, offset),
+            DecodeError::Mismatch { offset, tag } =>
+                write!(f, "record at byte {} does not match the struct of tag {}", offset, tag),
+            DecodeError::Io(kind) => write!(f, "I/O error: {}", kind),
+        }
+    }
+}
+
+
+impl std::error::Error for DecodeError {}
+
+
+impl From<io::Error> for DecodeError
+{
+    fn from(e: io::Error) -> Self
+    {
+        DecodeError::Io(e.kind())
+    }
+}
+
+
+impl Content
+{
+    /// The tag of the records holding this struct.
+    pub fn tag(&self) -> u8
+    {
+        match self
+        {
+            Content::ValueStruct { .. } => VALUE,
+            Content::MValueStruct { .. } => MVALUE,
+            Content::MessageStruct { .. } => MESSAGE,
+        }
+    }
+
+
+    pub fn type_(&self) -> i32
+    {
+        match self
+        {
+            Content::ValueStruct { type_, .. }
+            | Content::MValueStruct { type_, .. }
+            | Content::MessageStruct { type_, .. } => *type_,
+        }
+    }
+
+
+    /// The message up to the NULs padding it, for messages.
+    pub fn text(&self) -> Option<String>
+    {
+        match self
+        {
+            Content::MessageStruct { message, .. } => Some(message_text::trimmed(message)),
+            _ => None,
+        }
+    }
+
+
+    /// The values of the fields, in the order of the layout of the struct.
+    fn values(&self) -> Vec<Value>
+    {
+        match self
+        {
+            Content::ValueStruct { type_, val, timestamp } =>
+                vec![Value::I32(*type_), Value::F32(*val), Value::I64(*timestamp)],
+            Content::MValueStruct { type_, vals, timestamp } =>
+            {
+                let vals = vals.iter().map(|val| Value::F32(*val)).collect();
+                vec![Value::I32(*type_), Value::Array(vals), Value::I64(*timestamp)]
+            }
+            Content::MessageStruct { type_, message } =>
+            {
+                let message = message.iter().map(|ch| Value::Char(*ch)).collect();
+                vec![Value::I32(*type_), Value::Array(message)]
+            }
+        }
+    }
+
+
+    /// The struct of `tag` from the values decoded with its layout, if they
+    /// fit it.
+    fn from_values(tag: u8, values: &[Value]) -> Option<Content>
+    {
+        Some(match (tag, values)
+        {
+            (VALUE, [Value::I32(type_), Value::F32(val), Value::I64(timestamp)]) =>
+                Content::ValueStruct { type_: *type_, val: *val, timestamp: *timestamp },
+            (MVALUE, [Value::I32(type_), Value::Array(vals), Value::I64(timestamp)]) =>
+                Content::MValueStruct { type_: *type_, vals: elements(vals, Value::as_f32)?, timestamp: *timestamp },
+            (MESSAGE, [Value::I32(type_), Value::Array(message)]) =>
+                Content::MessageStruct { type_: *type_, message: elements(message, Value::as_char)? },
+            _ => return None,
+        })
+    }
+}
+
+
+impl From<Content> for CData
+{
+    fn from(content: Content) -> Self
+    {
+        CData { tag: content.tag(), content }
+    }
+}
+
+
+impl From<CData> for Content
+{
+    fn from(record: CData) -> Self
+    {
+        record.content
+    }
+}
+
+
 impl CData
 {
-    pub fn from_file(input: BufReader<File>, size: usize, output: &mut Vec<CData>)
+    pub fn value(type_: i32, val: f32, timestamp: i64) -> CData
     {
-        let mut _type: u8;
-        let mut inner_type: i32;
-        let mut content: Content = Content::ValueStruct { _type: 0, val: 0.0, timestamp: 0 };
+        Content::ValueStruct { type_, val, timestamp }.into()
+    }
+
 
-        let mut offset: usize = 0;
-        let mut first_field_start: usize = 0;
-        let mut first_field_end = 0; 
+    pub fn mvalue(type_: i32, vals: [f32; 10], timestamp: i64) -> CData
+    {
+        Content::MValueStruct { type_, vals, timestamp }.into()
+    }
 
-        let bytes: Vec<u8> = input.bytes()
-            .map(|r| r.unwrap())
-            .collect(); 
 
+    /// A message of up to 20 characters, padded with NULs.
+    pub fn message(type_: i32, message: [char; MESSAGE_LEN]) -> CData
+    {
+        Content::MessageStruct { type_, message }.into()
+    }
+
+
+    pub fn tag(&self) -> u8
+    {
+        self.tag
+    }
+
+
+    pub fn content(&self) -> &Content
+    {
+        &self.content
+    }
+
+
+    /// The layouts of the three structs, from which records are decoded and
+    /// encoded. Schemas for more records can start from these.
+    pub fn schema() -> &'static Schema
+    {
+        static SCHEMA: OnceLock<Schema> = OnceLock::new();
+        SCHEMA.get_or_init(||
+        {
+            Schema::new(vec![
+                Layout::new("ValueStruct", VALUE)
+                    .field("type", Scalar::I32)
+                    .field("val", Scalar::F32)
+                    .field("timestamp", Scalar::I64),
+                Layout::new("MValueStruct", MVALUE)
+                    .field("type", Scalar::I32)
+                    .array("vals", Scalar::F32, 10)
+                    .field("timestamp", Scalar::I64),
+                Layout::new("MessageStruct", MESSAGE)
+                    .field("type", Scalar::I32)
+                    .array("message", Scalar::Char32, MESSAGE_LEN),
+            ]).expect("the tags are different")
+        })
+    }
+
+
+    /// Read exactly `size` records, with nothing after them.
+    /// [`CDataReader`] reads them one at a time instead, until the end.
+    pub fn from_file(input: BufReader<File>, size: usize) -> Result<Vec<CData>, DecodeError>
+    {
+        let mut reader = CDataReader::new(input);
+        let mut output = Vec::with_capacity(size);
         for _ in 0..size
         {
-            _type = bytes[offset];
-            first_field_end = first_field_start + 4;
+            match reader.next()
+            {
+                Some(record) => output.push(record?),
+                None => return Err(DecodeError::Truncated { offset: reader.offset, needed: 1, available: 0 }),
+            }
+        }
 
-            inner_type = i32::from_le_bytes(bytes[first_field_start..first_field_end].try_into().unwrap());
-            match _type
+        let len = io::copy(&mut reader.input, &mut io::sink())? as usize;
+        if len > 0
+        {
+            return Err(DecodeError::TrailingGarbage { offset: reader.offset, len });
+        }
+        Ok(output)
+    }
+
+
+    /// All the records in `bytes`, stopping at the first error.
+    pub fn decode(bytes: &[u8]) -> Result<Vec<CData>, DecodeError>
+    {
+        let mut offset = 0;
+        CData::schema().decode(bytes)?.into_iter().map(|record|
+        {
+            let size = record_size(record.tag).unwrap_or(RECORD_ALIGN);
+            let data = CData::from_record(record, offset);
+            offset += size;
+            data
+        }).collect()
+    }
+
+
+    /// Like [`CData::decode`], skipping the bad records. Returns the good
+    /// ones and the errors of the others, in order.
+    ///
+    /// After an unknown tag the next record is looked for at the following
+    /// multiples of 4 bytes; a truncated record ends the data.
+    pub fn decode_lenient(bytes: &[u8]) -> (Vec<CData>, Vec<DecodeError>)
+    {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len()
+        {
+            match CData::decode_record(bytes, offset)
             {
-                0 =>
+                Ok((record, len)) =>
                 {
-                    offset += 24;
-                    let second_field_end = first_field_end + 4;
-                    let third_field_end = second_field_end + 8;
-
-                    let val: f32 = f32::from_le_bytes(bytes[first_field_end..second_field_end].try_into().unwrap());
-                    let timestamp: i64 = i64::from_le_bytes(bytes[second_field_end..third_field_end].try_into().unwrap());
-                    
-                    content = Content::ValueStruct { _type: inner_type, val: val, timestamp: timestamp };
+                    output.push(record);
+                    offset += len;
                 }
-
-                1 =>
+                Err(e @ (DecodeError::InvalidChar { .. } | DecodeError::Mismatch { .. })) =>
                 {
-                    offset += 64;
-                    let second_field_end = first_field_end + 40;
-                    let third_field_end = second_field_end + 8;
-
-                    let mut vals: [f32; 10] = [0.0; 10];
-                    for j in 0..10
+                    errors.push(e);
+                    offset += record_size(bytes[offset]).unwrap_or(RECORD_ALIGN);
+                }
+                Err(e @ DecodeError::UnknownTag { .. }) =>
+                {
+                    errors.push(e);
+                    offset += RECORD_ALIGN;
+                    while offset < bytes.len() && record_size(bytes[offset]).is_none()
                     {
-                        vals[j] = f32::from_le_bytes(bytes[first_field_end + j .. second_field_end + j].try_into().unwrap());
+                        offset += RECORD_ALIGN;
                     }
-                    let timestamp: i64 = i64::from_le_bytes(bytes[second_field_end..third_field_end].try_into().unwrap());
-                    content = Content::MValueStruct { _type: inner_type, vals: vals, timestamp: timestamp };
+                }
+                Err(e) =>
+                {
+                    errors.push(e);
+                    break;
+                }
+            }
+        }
+        (output, errors)
+    }
 
-                } 
 
-                2 =>
-                {
-                    offset += 88;
-                    let second_field_end = first_field_end + 84;
+    /// The record at `offset` and its size in bytes.
+    fn decode_record(bytes: &[u8], offset: usize) -> Result<(CData, usize), DecodeError>
+    {
+        let (record, size) = CData::schema().read(bytes, offset)?;
+        Ok((CData::from_record(record, offset)?, size))
+    }
+
+
+    /// The whole `record`, tag included, found at `offset`.
+    fn parse(record: &[u8], offset: usize) -> Result<CData, DecodeError>
+    {
+        let tag = record[0];
+        let layout = CData::schema().layout(tag).ok_or(DecodeError::UnknownTag { offset, tag })?;
+        let values = layout.decode(record, offset)?;
+        CData::from_record(Record { tag, values }, offset)
+    }
+
+
+    /// The struct of a `record` decoded with [`CData::schema`] at `offset`.
+    fn from_record(record: Record, offset: usize) -> Result<CData, DecodeError>
+    {
+        let Record { tag, values } = record;
+        let content = Content::from_values(tag, &values).ok_or(DecodeError::Mismatch { offset, tag })?;
+        Ok(CData { tag, content })
+    }
+}
+
+
+impl CData
+{
+    /// The record in the layout the decoders read, with zeros as padding.
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let layout = CData::schema().layout(self.tag).expect("every tag has a layout");
+        layout.encode(&self.content.values()).expect("the values match the layout")
+    }
+
+
+    /// The records one after the other, as [`CData::decode`] reads them.
+    pub fn encode(records: &[CData]) -> Vec<u8>
+    {
+        records.iter().flat_map(|record| record.to_bytes()).collect()
+    }
 
-                    let mut message: [char; 21] = ['\0'; 21];
-                    for j in 0..21
-                    {
-                        let ch = u32::from_le_bytes(bytes[first_field_end + j ..second_field_end + j].try_into().unwrap());
-                        message[j] = char::from_u32(ch).unwrap();
-                    }
-                    content = Content::MessageStruct { _type: inner_type, message: message };
-                }
 
-                _ =>
+    pub fn write_to(&self, out: &mut impl Write) -> io::Result<()>
+    {
+        out.write_all(&self.to_bytes())
+    }
+}
+
+
+/// Reads the records one at a time from any reader, until the end of the
+/// data, holding at most one record in memory.
+///
+/// Unknown tags, truncated records and I/O errors end the iteration, as
+/// the next record cannot be found after them, unless the reader is
+/// [lenient](Self::lenient) about unknown tags. A record whose values do not
+/// decode, such as a message with an invalid character, is yielded as an
+/// error and the iteration goes on with the next one.
+pub struct CDataReader<R: Read>
+{
+    input: R,
+    offset: usize,
+    done: bool,
+    lenient: bool,
+    /// Looking for the next known tag after an unknown one.
+    resync: bool,
+}
+
+
+impl<R: Read> CDataReader<R>
+{
+    pub fn new(input: R) -> Self
+    {
+        CDataReader { input, offset: 0, done: false, lenient: false, resync: false }
+    }
+
+
+    /// Go on after an unknown tag, yielding it as an error, from the next
+    /// multiple of 4 bytes with a known tag, like [`CData::decode_lenient`].
+    pub fn lenient(mut self) -> Self
+    {
+        self.lenient = true;
+        self
+    }
+
+
+    /// Bytes read so far.
+    pub fn offset(&self) -> usize
+    {
+        self.offset
+    }
+
+
+    /// The next record, or nothing at the end of the data.
+    fn read_record(&mut self) -> Result<Option<CData>, DecodeError>
+    {
+        let (offset, tag) = loop
+        {
+            if self.resync
+            {
+                // The rest of the group of 4 bytes the unknown tag started
+                let mut rest = [0; RECORD_ALIGN - 1];
+                if self.fill(&mut rest)? < rest.len()
                 {
-                    eprintln!("Unsupported structure");
-                    exit(1);
+                    return Ok(None);
                 }
-                
             }
-            output.push(CData { _type: _type, content: content });
-            first_field_start += offset;
+            let offset = self.offset;
+            let mut tag = [0];
+            if self.fill(&mut tag)? == 0
+            {
+                return Ok(None);
+            }
+            if !self.resync || record_size(tag[0]).is_some()
+            {
+                break (offset, tag);
+            }
+        };
+        self.resync = false;
+        let size = record_size(tag[0]).ok_or(DecodeError::UnknownTag { offset, tag: tag[0] })?;
+        let mut record = vec![tag[0]; size];
+        let available = 1 + self.fill(&mut record[1..])?;
+        if available < size
+        {
+            return Err(DecodeError::Truncated { offset, needed: size, available });
+        }
+        CData::parse(&record, offset).map(Some)
+    }
+
+
+    /// Read into `buf` until it is full or the data ends. Returns the bytes read.
+    fn fill(&mut self, buf: &mut [u8]) -> io::Result<usize>
+    {
+        let mut read = 0;
+        while read < buf.len()
+        {
+            match self.input.read(&mut buf[read..])
+            {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.offset += read;
+        Ok(read)
+    }
+}
+
+
+impl<R: Read> Iterator for CDataReader<R>
+{
+    type Item = Result<CData, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done
+        {
+            return None;
+        }
+        match self.read_record()
+        {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) =>
+            {
+                self.done = true;
+                None
+            }
+            Err(e @ (DecodeError::InvalidChar { .. } | DecodeError::Mismatch { .. })) => Some(Err(e)),
+            Err(e @ DecodeError::UnknownTag { .. }) if self.lenient =>
+            {
+                self.resync = true;
+                Some(Err(e))
+            }
+            Err(e) =>
+            {
+                self.done = true;
+                Some(Err(e))
+            }
         }
-        
     }
+}
+
+
+fn record_size(tag: u8) -> Option<usize>
+{
+    CData::schema().layout(tag).map(Layout::size)
+}
+
+
+/// The array of the elements of `values`, if there are `N` of them and `get`
+/// reads them all.
+fn elements<T: Copy + Default, const N: usize>(values: &[Value], get: impl Fn(&Value) -> Option<T>) -> Option<[T; N]>
+{
+    if values.len() != N
+    {
+        return None;
+    }
+    let mut array = [T::default(); N];
+    for (element, value) in array.iter_mut().zip(values)
+    {
+        *element = get(value)?;
+    }
+    Some(array)
+}
+
+
+/// Messages as text: the trailing NULs are dropped and put back.
+mod message_text
+{
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+
+    pub fn trimmed(message: &[char; super::MESSAGE_LEN]) -> String
+    {
+        message.iter().collect::<String>().trim_end_matches('\0').to_string()
+    }
+
+
+    pub fn serialize<S: Serializer>(message: &[char; super::MESSAGE_LEN], serializer: S) -> Result<S::Ok, S::Error>
+    {
+        serializer.serialize_str(&trimmed(message))
+    }
+
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[char; super::MESSAGE_LEN], D::Error>
+    {
+        let text = String::deserialize(deserializer)?;
+        let len = text.chars().count();
+        if len > super::MESSAGE_LEN
+        {
+            return Err(de::Error::invalid_length(len, &"at most 20 characters"));
+        }
+        let mut message = ['\0'; super::MESSAGE_LEN];
+        for (ch, c) in message.iter_mut().zip(text.chars())
+        {
+            *ch = c;
+        }
+        Ok(message)
+    }
+}
 
-    
-}
\ No newline at end of file
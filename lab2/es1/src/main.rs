@@ -1,29 +1,116 @@
-use std::{env::args, process::exit};
-use std::fs::File;
-use std::io::BufReader;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{stdout, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::exit;
 
-use es1::CData;
+use clap::Parser;
 
-fn main() 
+use es1::CDataReader;
+use es1::export::{write_json_line, CsvTables};
+
+
+/// Count the records of a file written by the C exporter, or dump them as
+/// JSON lines and CSV tables.
+///
+/// Decoding stops at the first bad record, unless --lenient skips it.
+#[derive(Parser, Debug)]
+struct Arguments
+{
+    file: PathBuf,
+
+    /// Skip the bad records, reporting them on stderr
+    #[arg(long)]
+    lenient: bool,
+
+    /// Print the records as JSON lines on stdout
+    #[arg(long)]
+    json: bool,
+
+    /// Write the records to values.csv, mvalues.csv and messages.csv in DIR
+    #[arg(long, value_name = "DIR")]
+    csv: Option<PathBuf>,
+}
+
+
+fn main()
 {
-    const SIZE: usize = 100;
-    let args: Vec<String> = args().skip(1).collect();
-    if args.is_empty()
+    let args = Arguments::parse();
+    if let Err(e) = run(&args)
     {
-        println!("Missing arguments");
+        eprintln!("es1: {}", e);
         exit(1);
     }
+}
+
+
+fn run(args: &Arguments) -> Result<(), Box<dyn Error>>
+{
+    let input = File::open(&args.file).map_err(|e| format!("cannot read {}: {}", args.file.display(), e))?;
+    let mut records = CDataReader::new(BufReader::new(input));
+    if args.lenient
+    {
+        records = records.lenient();
+    }
 
-    let input = File::open(&args[0]);
-    match input
+    let mut json = args.json.then(|| BufWriter::new(stdout().lock()));
+    let mut tables = args.csv.as_deref().map(csv_tables).transpose()?;
+    let (mut count, mut skipped) = (0, 0);
+    for record in records
     {
-        Ok(f) => 
+        let record = match record
+        {
+            Ok(record) => record,
+            Err(e) if args.lenient =>
+            {
+                eprintln!("Skipped: {}", e);
+                skipped += 1;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(out) = json.as_mut()
         {
-            let buffer = BufReader::new(f);
-            let mut cdata: Vec<CData> = Vec::new(); 
+            write_json_line(out, &record)?;
+        }
+        if let Some(tables) = tables.as_mut()
+        {
+            tables.write(&record)?;
+        }
+        count += 1;
+    }
 
-            CData::from_file(buffer, SIZE, &mut cdata);
-        },
-        Err(_) => exit(1), 
+    if let Some(mut out) = json
+    {
+        out.flush()?;
+    }
+    if let Some(tables) = tables
+    {
+        let (mut values, mut mvalues, mut messages) = tables.finish()?;
+        values.flush()?;
+        mvalues.flush()?;
+        messages.flush()?;
+    }
+    // The dumps are the output, and stdout may hold JSON lines
+    if args.json || args.csv.is_some()
+    {
+        return Ok(());
     }
+    if args.lenient
+    {
+        println!("{} records, {} skipped", count, skipped);
+    }
+    else
+    {
+        println!("{} records", count);
+    }
+    Ok(())
+}
+
+
+fn csv_tables(dir: &Path) -> Result<CsvTables<BufWriter<File>>, Box<dyn Error>>
+{
+    fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    let table = |name: &str| File::create(dir.join(name)).map(BufWriter::new);
+    Ok(CsvTables::new(table("values.csv")?, table("mvalues.csv")?, table("messages.csv")?)?)
 }
@@ -0,0 +1,494 @@
+//! Runtime descriptions of C struct layouts, from which records are decoded
+//! and encoded.
+//!
+//! A record is a tag byte followed by the struct, laid out as a C compiler
+//! would: every field at a multiple of the size of its type, the struct at a
+//! multiple of its largest type and padded to a multiple of it, padding
+//! bytes being zeros. [`CData::schema`](crate::CData::schema) describes the
+//! three records of [`CData`](crate::CData).
+
+use std::fmt;
+
+use crate::DecodeError;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian
+{
+    Little,
+    Big,
+}
+
+
+/// The C types fields are made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scalar
+{
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    /// A UTF-32 code unit, read as a `char`.
+    Char32,
+}
+
+
+impl Scalar
+{
+    /// Size in bytes, which is also the alignment.
+    pub fn size(self) -> usize
+    {
+        match self
+        {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 | Scalar::Char32 => 4,
+            Scalar::I64 | Scalar::U64 | Scalar::F64 => 8,
+        }
+    }
+}
+
+
+/// The value of a field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value
+{
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Array(Vec<Value>),
+}
+
+
+impl Value
+{
+    pub fn as_i32(&self) -> Option<i32>
+    {
+        match self
+        {
+            Value::I32(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_i64(&self) -> Option<i64>
+    {
+        match self
+        {
+            Value::I64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_f32(&self) -> Option<f32>
+    {
+        match self
+        {
+            Value::F32(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_char(&self) -> Option<char>
+    {
+        match self
+        {
+            Value::Char(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+
+    pub fn as_array(&self) -> Option<&[Value]>
+    {
+        match self
+        {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+
+    /// Whether the value can be stored as `scalar`.
+    fn is(&self, scalar: Scalar) -> bool
+    {
+        matches!((self, scalar),
+            (Value::I8(_), Scalar::I8) | (Value::U8(_), Scalar::U8)
+            | (Value::I16(_), Scalar::I16) | (Value::U16(_), Scalar::U16)
+            | (Value::I32(_), Scalar::I32) | (Value::U32(_), Scalar::U32)
+            | (Value::I64(_), Scalar::I64) | (Value::U64(_), Scalar::U64)
+            | (Value::F32(_), Scalar::F32) | (Value::F64(_), Scalar::F64)
+            | (Value::Char(_), Scalar::Char32))
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError
+{
+    /// Two layouts have the same tag.
+    DuplicateTag(u8),
+    UnknownTag(u8),
+    /// `found` values were given for `expected` fields.
+    FieldCount { expected: usize, found: usize },
+    /// The value of the field does not have its type or its length.
+    Mismatch { field: String },
+}
+
+
+impl fmt::Display for SchemaError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self
+        {
+            SchemaError::DuplicateTag(tag) => write!(f, "more than one layout for tag {}", tag),
+            SchemaError::UnknownTag(tag) => write!(f, "no layout for tag {}", tag),
+            SchemaError::FieldCount { expected, found } =>
+                write!(f, "{} values given for {} fields", found, expected),
+            SchemaError::Mismatch { field } => write!(f, "the value of {} does not match its type", field),
+        }
+    }
+}
+
+
+impl std::error::Error for SchemaError {}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field
+{
+    pub name: String,
+    pub scalar: Scalar,
+    /// Number of elements of an array, `None` for a single value.
+    pub len: Option<usize>,
+    /// Offset from the start of the struct.
+    offset: usize,
+}
+
+
+/// The layout of the record with a given tag, built field by field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout
+{
+    name: String,
+    tag: u8,
+    endian: Endian,
+    fields: Vec<Field>,
+    /// Where the last field ends in the struct.
+    end: usize,
+}
+
+
+impl Layout
+{
+    /// An empty little-endian struct.
+    pub fn new(name: impl Into<String>, tag: u8) -> Layout
+    {
+        Layout { name: name.into(), tag, endian: Endian::Little, fields: Vec::new(), end: 0 }
+    }
+
+
+    pub fn endian(mut self, endian: Endian) -> Self
+    {
+        self.endian = endian;
+        self
+    }
+
+
+    pub fn field(self, name: impl Into<String>, scalar: Scalar) -> Self
+    {
+        self.push(name.into(), scalar, None)
+    }
+
+
+    pub fn array(self, name: impl Into<String>, scalar: Scalar, len: usize) -> Self
+    {
+        self.push(name.into(), scalar, Some(len))
+    }
+
+
+    fn push(mut self, name: String, scalar: Scalar, len: Option<usize>) -> Self
+    {
+        let offset = self.end.next_multiple_of(scalar.size());
+        self.end = offset + scalar.size() * len.unwrap_or(1);
+        self.fields.push(Field { name, scalar, len, offset });
+        self
+    }
+
+
+    pub fn name(&self) -> &str
+    {
+        &self.name
+    }
+
+
+    pub fn tag(&self) -> u8
+    {
+        self.tag
+    }
+
+
+    pub fn fields(&self) -> &[Field]
+    {
+        &self.fields
+    }
+
+
+    /// Alignment of the struct: that of its largest type.
+    pub fn align(&self) -> usize
+    {
+        self.fields.iter().map(|f| f.scalar.size()).max().unwrap_or(1)
+    }
+
+
+    /// Offset of field `index` from the start of the record.
+    pub fn offset(&self, index: usize) -> usize
+    {
+        self.align() + self.fields[index].offset
+    }
+
+
+    /// Size of the record, tag and padding included.
+    pub fn size(&self) -> usize
+    {
+        self.align() + self.end.next_multiple_of(self.align())
+    }
+
+
+    /// The values of the fields of `record`, found at `offset` in the data.
+    pub fn decode(&self, record: &[u8], offset: usize) -> Result<Vec<Value>, DecodeError>
+    {
+        let size = self.size();
+        if record.len() < size
+        {
+            return Err(DecodeError::Truncated { offset, needed: size, available: record.len() });
+        }
+
+        let mut values = Vec::with_capacity(self.fields.len());
+        for (i, field) in self.fields.iter().enumerate()
+        {
+            let start = self.offset(i);
+            let width = field.scalar.size();
+            let read = |index: usize|
+            {
+                let bytes = &record[start + index * width..start + (index + 1) * width];
+                read(field.scalar, bytes, self.endian)
+                    .map_err(|code| DecodeError::InvalidChar { offset, index, code })
+            };
+            values.push(match field.len
+            {
+                None => read(0)?,
+                Some(len) => Value::Array((0..len).map(read).collect::<Result<_, _>>()?),
+            });
+        }
+        Ok(values)
+    }
+
+
+    /// The record holding `values`, one for each field.
+    pub fn encode(&self, values: &[Value]) -> Result<Vec<u8>, SchemaError>
+    {
+        if values.len() != self.fields.len()
+        {
+            return Err(SchemaError::FieldCount { expected: self.fields.len(), found: values.len() });
+        }
+
+        let mut record = vec![0; self.size()];
+        record[0] = self.tag;
+        for (i, (field, value)) in self.fields.iter().zip(values).enumerate()
+        {
+            let elements = match (field.len, value)
+            {
+                (None, value) => std::slice::from_ref(value),
+                (Some(len), Value::Array(elements)) if elements.len() == len => elements,
+                _ => return Err(SchemaError::Mismatch { field: field.name.clone() }),
+            };
+            let width = field.scalar.size();
+            for (j, element) in elements.iter().enumerate()
+            {
+                if !element.is(field.scalar)
+                {
+                    return Err(SchemaError::Mismatch { field: field.name.clone() });
+                }
+                let start = self.offset(i) + j * width;
+                write(element, &mut record[start..start + width], self.endian);
+            }
+        }
+        Ok(record)
+    }
+}
+
+
+/// A record decoded through a [`Schema`]: the values of the fields of the
+/// layout of its tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record
+{
+    pub tag: u8,
+    pub values: Vec<Value>,
+}
+
+
+/// The layouts of the records that can appear in the same data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema
+{
+    layouts: Vec<Layout>,
+}
+
+
+impl Schema
+{
+    pub fn new(layouts: Vec<Layout>) -> Result<Schema, SchemaError>
+    {
+        for (i, layout) in layouts.iter().enumerate()
+        {
+            if layouts[..i].iter().any(|other| other.tag == layout.tag)
+            {
+                return Err(SchemaError::DuplicateTag(layout.tag));
+            }
+        }
+        Ok(Schema { layouts })
+    }
+
+
+    pub fn layouts(&self) -> &[Layout]
+    {
+        &self.layouts
+    }
+
+
+    pub fn layout(&self, tag: u8) -> Option<&Layout>
+    {
+        self.layouts.iter().find(|layout| layout.tag == tag)
+    }
+
+
+    /// Size of the largest record.
+    pub fn max_size(&self) -> usize
+    {
+        self.layouts.iter().map(Layout::size).max().unwrap_or(1)
+    }
+
+
+    /// The record at `offset` and its size in bytes.
+    pub fn read(&self, bytes: &[u8], offset: usize) -> Result<(Record, usize), DecodeError>
+    {
+        let available = bytes.len().saturating_sub(offset);
+        let tag = *bytes.get(offset).ok_or(DecodeError::Truncated { offset, needed: 1, available })?;
+        let layout = self.layout(tag).ok_or(DecodeError::UnknownTag { offset, tag })?;
+        let values = layout.decode(&bytes[offset..], offset)?;
+        Ok((Record { tag, values }, layout.size()))
+    }
+
+
+    /// All the records in `bytes`, stopping at the first error.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<Record>, DecodeError>
+    {
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len()
+        {
+            let (record, size) = self.read(bytes, offset)?;
+            records.push(record);
+            offset += size;
+        }
+        Ok(records)
+    }
+
+
+    pub fn encode(&self, record: &Record) -> Result<Vec<u8>, SchemaError>
+    {
+        self.layout(record.tag).ok_or(SchemaError::UnknownTag(record.tag))?.encode(&record.values)
+    }
+}
+
+
+macro_rules! from_bytes
+{
+    ($t:ty, $bytes:expr, $endian:expr) =>
+    {{
+        let bytes = $bytes.try_into().expect("sliced to the size of the type");
+        match $endian
+        {
+            Endian::Little => <$t>::from_le_bytes(bytes),
+            Endian::Big => <$t>::from_be_bytes(bytes),
+        }
+    }};
+}
+
+
+macro_rules! to_bytes
+{
+    ($v:expr, $out:expr, $endian:expr) =>
+    {
+        $out.copy_from_slice(&match $endian
+        {
+            Endian::Little => $v.to_le_bytes(),
+            Endian::Big => $v.to_be_bytes(),
+        })
+    };
+}
+
+
+/// The value of `scalar` in `bytes`, or the code unit that is not a `char`.
+fn read(scalar: Scalar, bytes: &[u8], endian: Endian) -> Result<Value, u32>
+{
+    Ok(match scalar
+    {
+        Scalar::I8 => Value::I8(from_bytes!(i8, bytes, endian)),
+        Scalar::U8 => Value::U8(from_bytes!(u8, bytes, endian)),
+        Scalar::I16 => Value::I16(from_bytes!(i16, bytes, endian)),
+        Scalar::U16 => Value::U16(from_bytes!(u16, bytes, endian)),
+        Scalar::I32 => Value::I32(from_bytes!(i32, bytes, endian)),
+        Scalar::U32 => Value::U32(from_bytes!(u32, bytes, endian)),
+        Scalar::I64 => Value::I64(from_bytes!(i64, bytes, endian)),
+        Scalar::U64 => Value::U64(from_bytes!(u64, bytes, endian)),
+        Scalar::F32 => Value::F32(from_bytes!(f32, bytes, endian)),
+        Scalar::F64 => Value::F64(from_bytes!(f64, bytes, endian)),
+        Scalar::Char32 =>
+        {
+            let code = from_bytes!(u32, bytes, endian);
+            Value::Char(char::from_u32(code).ok_or(code)?)
+        }
+    })
+}
+
+
+/// Store a single value, already checked against its scalar, in `out`.
+fn write(value: &Value, out: &mut [u8], endian: Endian)
+{
+    match value
+    {
+        Value::I8(v) => to_bytes!(v, out, endian),
+        Value::U8(v) => to_bytes!(v, out, endian),
+        Value::I16(v) => to_bytes!(v, out, endian),
+        Value::U16(v) => to_bytes!(v, out, endian),
+        Value::I32(v) => to_bytes!(v, out, endian),
+        Value::U32(v) => to_bytes!(v, out, endian),
+        Value::I64(v) => to_bytes!(v, out, endian),
+        Value::U64(v) => to_bytes!(v, out, endian),
+        Value::F32(v) => to_bytes!(v, out, endian),
+        Value::F64(v) => to_bytes!(v, out, endian),
+        Value::Char(c) => to_bytes!(*c as u32, out, endian),
+        Value::Array(_) => {}
+    }
+}
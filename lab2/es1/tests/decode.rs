@@ -0,0 +1,198 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+
+use es1::{CData, CDataReader, DecodeError};
+
+/// Hands out the data a few bytes at a time, then fails if `error` is set.
+struct Trickle {
+    data: Vec<u8>,
+    pos: usize,
+    error: Option<io::ErrorKind>,
+}
+
+impl Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.data.len() {
+            return match self.error {
+                Some(kind) => Err(kind.into()),
+                None => Ok(0),
+            };
+        }
+        let n = buf.len().min(3).min(self.data.len() - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn trickle(data: Vec<u8>) -> Trickle {
+    Trickle { data, pos: 0, error: None }
+}
+
+/// How many records the reader yields, and its errors.
+fn read_all<R: Read>(reader: CDataReader<R>) -> (usize, Vec<DecodeError>) {
+    let (records, errors): (Vec<_>, Vec<_>) = reader.partition(|r| r.is_ok());
+    (records.len(), errors.into_iter().map(|e| e.err().unwrap()).collect())
+}
+
+fn value(type_: i32, val: f32, timestamp: i64) -> Vec<u8> {
+    let mut record = vec![0; 24];
+    record[8..12].copy_from_slice(&type_.to_le_bytes());
+    record[12..16].copy_from_slice(&val.to_le_bytes());
+    record[16..24].copy_from_slice(&timestamp.to_le_bytes());
+    record
+}
+
+fn mvalue(type_: i32, vals: [f32; 10], timestamp: i64) -> Vec<u8> {
+    let mut record = vec![0; 64];
+    record[0] = 1;
+    record[8..12].copy_from_slice(&type_.to_le_bytes());
+    for (j, val) in vals.iter().enumerate() {
+        record[12 + 4 * j..16 + 4 * j].copy_from_slice(&val.to_le_bytes());
+    }
+    record[56..64].copy_from_slice(&timestamp.to_le_bytes());
+    record
+}
+
+fn message(type_: i32, codes: &[u32]) -> Vec<u8> {
+    let mut record = vec![0; 88];
+    record[0] = 2;
+    record[4..8].copy_from_slice(&type_.to_le_bytes());
+    for (j, code) in codes.iter().enumerate() {
+        record[8 + 4 * j..12 + 4 * j].copy_from_slice(&code.to_le_bytes());
+    }
+    record
+}
+
+fn text(s: &str) -> Vec<u32> {
+    s.chars().map(|c| c as u32).collect()
+}
+
+fn from_file(name: &str, bytes: &[u8], size: usize) -> Result<usize, DecodeError> {
+    let path = std::env::temp_dir().join(format!("es1-{}-{}", std::process::id(), name));
+    fs::write(&path, bytes).unwrap();
+    let result = CData::from_file(BufReader::new(File::open(&path).unwrap()), size).map(|r| r.len());
+    fs::remove_file(&path).unwrap();
+    result
+}
+
+#[test]
+fn every_variant_is_decoded() {
+    let bytes = [value(1, 2.5, 100), mvalue(2, [1.0; 10], 200), message(3, &text("ciao"))].concat();
+    assert_eq!(CData::decode(&bytes).map(|r| r.len()), Ok(3));
+    assert_eq!(CData::decode(&[]).map(|r| r.len()), Ok(0));
+}
+
+#[test]
+fn truncated_record() {
+    let bytes = [value(1, 2.5, 100), mvalue(2, [1.0; 10], 200)].concat();
+    assert_eq!(
+        CData::decode(&bytes[..70]).err(),
+        Some(DecodeError::Truncated { offset: 24, needed: 64, available: 46 })
+    );
+}
+
+#[test]
+fn unknown_tag_with_its_offset() {
+    let mut bytes = [value(1, 2.5, 100), value(1, 2.5, 100)].concat();
+    bytes[24] = 7;
+    assert_eq!(CData::decode(&bytes).err(), Some(DecodeError::UnknownTag { offset: 24, tag: 7 }));
+}
+
+#[test]
+fn invalid_code_point_in_a_message() {
+    let mut codes = text("ciao");
+    codes[3] = 0xD800;
+    let bytes = [value(1, 2.5, 100), message(3, &codes)].concat();
+    assert_eq!(
+        CData::decode(&bytes).err(),
+        Some(DecodeError::InvalidChar { offset: 24, index: 3, code: 0xD800 })
+    );
+}
+
+#[test]
+fn from_file_reads_exactly_the_records() {
+    let bytes = [value(1, 2.5, 100), message(3, &text("ciao"))].concat();
+    assert_eq!(from_file("exact", &bytes, 2), Ok(2));
+    assert_eq!(
+        from_file("short", &bytes, 3),
+        Err(DecodeError::Truncated { offset: 112, needed: 1, available: 0 })
+    );
+    assert_eq!(
+        from_file("garbage", &[bytes.as_slice(), &[0; 5]].concat(), 2),
+        Err(DecodeError::TrailingGarbage { offset: 112, len: 5 })
+    );
+}
+
+#[test]
+fn lenient_mode_skips_bad_records() {
+    let mut codes = text("ciao");
+    codes[0] = 0x110000;
+    let bytes = [
+        value(1, 2.5, 100),
+        vec![9, 0, 0, 0, 5, 5, 5, 5],
+        message(3, &codes),
+        mvalue(2, [1.0; 10], 200),
+        value(1, 2.5, 100)[..10].to_vec(),
+    ]
+    .concat();
+
+    let (records, errors) = CData::decode_lenient(&bytes);
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        errors,
+        vec![
+            DecodeError::UnknownTag { offset: 24, tag: 9 },
+            DecodeError::InvalidChar { offset: 32, index: 0, code: 0x110000 },
+            DecodeError::Truncated { offset: 184, needed: 24, available: 10 },
+        ]
+    );
+
+    // The same while streaming
+    assert_eq!(read_all(CDataReader::new(trickle(bytes)).lenient()), (2, errors));
+}
+
+#[test]
+fn reader_yields_records_until_the_end() {
+    let bytes = [value(1, 2.5, 100), mvalue(2, [1.0; 10], 200), message(3, &text("ciao"))].concat();
+    let mut reader = CDataReader::new(trickle(bytes.clone()));
+    assert!(reader.next().unwrap().is_ok());
+    assert_eq!(reader.offset(), 24);
+    assert_eq!(reader.count(), 2);
+
+    assert_eq!(read_all(CDataReader::new(bytes.as_slice())), (3, vec![]));
+    assert_eq!(read_all(CDataReader::new(io::empty())), (0, vec![]));
+}
+
+#[test]
+fn reader_stops_where_the_data_cannot_be_followed() {
+    let bytes = [value(1, 2.5, 100), mvalue(2, [1.0; 10], 200)].concat();
+    assert_eq!(
+        read_all(CDataReader::new(trickle(bytes[..70].to_vec()))),
+        (1, vec![DecodeError::Truncated { offset: 24, needed: 64, available: 46 }])
+    );
+
+    let mut tagged = [bytes.as_slice(), &bytes].concat();
+    tagged[24] = 7;
+    assert_eq!(
+        read_all(CDataReader::new(tagged.as_slice())),
+        (1, vec![DecodeError::UnknownTag { offset: 24, tag: 7 }])
+    );
+
+    let failing = Trickle { data: value(1, 2.5, 100), pos: 0, error: Some(io::ErrorKind::ConnectionReset) };
+    assert_eq!(
+        read_all(CDataReader::new(failing)),
+        (1, vec![DecodeError::Io(io::ErrorKind::ConnectionReset)])
+    );
+}
+
+#[test]
+fn reader_skips_invalid_messages() {
+    let mut codes = text("ciao");
+    codes[2] = 0xDFFF;
+    let bytes = [message(3, &codes), value(1, 2.5, 100)].concat();
+    assert_eq!(
+        read_all(CDataReader::new(trickle(bytes))),
+        (1, vec![DecodeError::InvalidChar { offset: 0, index: 2, code: 0xDFFF }])
+    );
+}
@@ -0,0 +1,88 @@
+use es1::{CData, CDataReader, Content};
+use proptest::prelude::*;
+
+/// Floats that compare equal to themselves.
+fn float() -> impl Strategy<Value = f32> {
+    prop::num::f32::NORMAL | prop::num::f32::SUBNORMAL | prop::num::f32::ZERO | prop::num::f32::INFINITE
+}
+
+/// Any record: every value of every field of the three structs.
+fn record() -> impl Strategy<Value = CData> {
+    prop_oneof![
+        (any::<i32>(), float(), any::<i64>()).prop_map(|(type_, val, timestamp)| Content::ValueStruct { type_, val, timestamp }),
+        (any::<i32>(), prop::array::uniform10(float()), any::<i64>())
+            .prop_map(|(type_, vals, timestamp)| Content::MValueStruct { type_, vals, timestamp }),
+        (any::<i32>(), prop::array::uniform20(any::<char>())).prop_map(|(type_, message)| Content::MessageStruct { type_, message }),
+    ]
+    .prop_map(CData::from)
+}
+
+fn message(text: &str) -> [char; 20] {
+    let mut message = ['\0'; 20];
+    for (ch, c) in message.iter_mut().zip(text.chars()) {
+        *ch = c;
+    }
+    message
+}
+
+#[test]
+fn value_layout() {
+    let mut expected = vec![0; 24];
+    expected[8..12].copy_from_slice(&(-3i32).to_le_bytes());
+    expected[12..16].copy_from_slice(&2.5f32.to_le_bytes());
+    expected[16..24].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+    assert_eq!(CData::value(-3, 2.5, 1_700_000_000).to_bytes(), expected);
+}
+
+#[test]
+fn mvalue_layout() {
+    let bytes = CData::mvalue(7, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0], -1).to_bytes();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(bytes[0], 1);
+    assert_eq!(&bytes[1..8], &[0; 7]);
+    assert_eq!(&bytes[8..12], &7i32.to_le_bytes());
+    assert_eq!(&bytes[48..52], &10.0f32.to_le_bytes());
+    // Padding before the timestamp
+    assert_eq!(&bytes[52..56], &[0; 4]);
+    assert_eq!(&bytes[56..64], &[0xff; 8]);
+}
+
+#[test]
+fn message_layout() {
+    let bytes = CData::message(5, message("né")).to_bytes();
+    assert_eq!(bytes.len(), 88);
+    assert_eq!(&bytes[..4], &[2, 0, 0, 0]);
+    assert_eq!(&bytes[4..8], &5i32.to_le_bytes());
+    assert_eq!(&bytes[8..16], &[b'n', 0, 0, 0, 0xe9, 0, 0, 0]);
+    assert_eq!(&bytes[16..], &[0; 72]);
+
+    // A full message has no NUL
+    let full = CData::message(5, ['x'; 20]);
+    assert_eq!(full.content().text(), Some("x".repeat(20)));
+    assert_eq!(CData::decode(&full.to_bytes()).unwrap(), vec![full]);
+}
+
+#[test]
+fn write_to_appends_the_record() {
+    let mut out = vec![9];
+    CData::value(1, 0.5, 2).write_to(&mut out).unwrap();
+    assert_eq!(out.len(), 25);
+    assert_eq!(CData::decode(&out[1..]).unwrap(), vec![CData::value(1, 0.5, 2)]);
+}
+
+proptest! {
+    #[test]
+    fn decode_inverts_encode(records in prop::collection::vec(record(), 0..16)) {
+        let bytes = CData::encode(&records);
+        prop_assert_eq!(&CData::decode(&bytes).unwrap(), &records);
+        let streamed: Vec<CData> = CDataReader::new(bytes.as_slice()).map(Result::unwrap).collect();
+        prop_assert_eq!(&streamed, &records);
+    }
+
+    #[test]
+    fn floats_keep_their_bits(bits in any::<u32>(), ts in any::<i64>()) {
+        // NaN is never equal to itself: compare the bytes instead
+        let bytes = CData::value(0, f32::from_bits(bits), ts).to_bytes();
+        prop_assert_eq!(CData::decode(&bytes).unwrap()[0].to_bytes(), bytes);
+    }
+}
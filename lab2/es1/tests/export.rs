@@ -0,0 +1,127 @@
+use std::fs;
+use std::process::Command;
+
+use es1::export::{write_json_line, CsvTables};
+use es1::{CData, Content};
+
+fn message(text: &str) -> [char; 20] {
+    let mut message = ['\0'; 20];
+    for (ch, c) in message.iter_mut().zip(text.chars()) {
+        *ch = c;
+    }
+    message
+}
+
+fn records() -> Vec<CData> {
+    vec![
+        CData::value(1, 2.5, 100),
+        CData::mvalue(2, [0.5; 10], -7),
+        CData::message(3, message("ciao, \"mondo\"")),
+    ]
+}
+
+#[test]
+fn accessors() {
+    let records = CData::decode(&CData::encode(&records())).unwrap();
+    assert_eq!(records.iter().map(|r| r.tag()).collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(records[0].content(), &Content::ValueStruct { type_: 1, val: 2.5, timestamp: 100 });
+    assert_eq!(records[1].content().type_(), 2);
+    assert_eq!(records[1].content().text(), None);
+    assert_eq!(records[2].content().text().as_deref(), Some("ciao, \"mondo\""));
+    assert_eq!(CData::from(records[0].content().clone()), records[0]);
+}
+
+#[test]
+fn json_lines() {
+    let mut out = Vec::new();
+    for record in records() {
+        write_json_line(&mut out, &record).unwrap();
+    }
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], r#"{"kind":"ValueStruct","type":1,"val":2.5,"timestamp":100}"#);
+    assert!(lines[1].starts_with(r#"{"kind":"MValueStruct","type":2,"vals":[0.5,0.5,"#));
+    assert_eq!(lines[2], r#"{"kind":"MessageStruct","type":3,"message":"ciao, \"mondo\""}"#);
+
+    let parsed: Vec<CData> = lines.iter().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(parsed, records());
+}
+
+#[test]
+fn json_messages_fit_the_struct() {
+    // A full message reads back, one more character does not fit
+    let full = CData::message(3, ['x'; 20]);
+    let json = serde_json::to_string(&full).unwrap();
+    assert_eq!(json, format!(r#"{{"kind":"MessageStruct","type":3,"message":"{}"}}"#, "x".repeat(20)));
+    assert_eq!(serde_json::from_str::<CData>(&json).unwrap(), full);
+
+    let long = format!(r#"{{"kind":"MessageStruct","type":3,"message":"{}"}}"#, "x".repeat(21));
+    let e = serde_json::from_str::<CData>(&long).unwrap_err();
+    assert!(e.to_string().contains("invalid length 21, expected at most 20 characters"), "{}", e);
+}
+
+#[test]
+fn one_csv_table_per_kind() {
+    let mut tables = CsvTables::new(Vec::new(), Vec::new(), Vec::new()).unwrap();
+    for record in records().iter().chain(&[CData::value(4, -1.0, 0)]) {
+        tables.write(record).unwrap();
+    }
+    let (values, mvalues, messages) = tables.finish().unwrap();
+
+    assert_eq!(String::from_utf8(values).unwrap(), "type,val,timestamp\n1,2.5,100\n4,-1,0\n");
+    assert_eq!(
+        String::from_utf8(mvalues).unwrap(),
+        "type,val0,val1,val2,val3,val4,val5,val6,val7,val8,val9,timestamp\n\
+         2,0.5,0.5,0.5,0.5,0.5,0.5,0.5,0.5,0.5,0.5,-7\n"
+    );
+    assert_eq!(String::from_utf8(messages).unwrap(), "type,message\n3,\"ciao, \"\"mondo\"\"\"\n");
+}
+
+#[test]
+fn command_line_dumps() {
+    let dir = std::env::temp_dir().join(format!("es1-export-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("records.bin");
+    fs::write(&input, CData::encode(&records())).unwrap();
+
+    let json = Command::new(env!("CARGO_BIN_EXE_es1")).arg(&input).arg("--json").output().unwrap();
+    assert!(json.status.success());
+    assert_eq!(String::from_utf8(json.stdout).unwrap().lines().count(), 3);
+
+    let tables = dir.join("tables");
+    let csv = Command::new(env!("CARGO_BIN_EXE_es1")).arg(&input).arg("--csv").arg(&tables).output().unwrap();
+    assert!(csv.status.success());
+    assert_eq!(fs::read_to_string(tables.join("values.csv")).unwrap(), "type,val,timestamp\n1,2.5,100\n");
+    assert_eq!(fs::read_to_string(tables.join("messages.csv")).unwrap().lines().count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn command_line_modes_combine() {
+    let dir = std::env::temp_dir().join(format!("es1-modes-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("records.bin");
+    let mut bytes = CData::encode(&records());
+    bytes.extend([9, 0, 0, 0]);
+    fs::write(&input, &bytes).unwrap();
+
+    let es1 = || Command::new(env!("CARGO_BIN_EXE_es1"));
+    let tables = dir.join("tables");
+    let both = es1().arg("--csv").arg(&tables).arg("--lenient").arg("--json").arg(&input).output().unwrap();
+    assert!(both.status.success());
+    assert_eq!(String::from_utf8(both.stdout).unwrap().lines().count(), 3);
+    assert!(String::from_utf8(both.stderr).unwrap().contains("unknown record tag 9"));
+    assert_eq!(fs::read_to_string(tables.join("values.csv")).unwrap().lines().count(), 2);
+
+    let strict = es1().arg(&input).output().unwrap();
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(String::from_utf8(strict.stderr).unwrap().contains("unknown record tag 9 at byte 176"));
+
+    let missing = es1().arg(dir.join("missing.bin")).output().unwrap();
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8(missing.stderr).unwrap().contains("cannot read"));
+
+    assert!(!es1().arg(&input).arg("--xml").output().unwrap().status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,103 @@
+use es1::schema::{Endian, Layout, Record, Scalar, Schema, SchemaError, Value};
+use es1::{CData, DecodeError};
+
+/// A firmware record with every kind of padding: after the tag, between
+/// fields and at the end.
+fn sensor() -> Layout {
+    Layout::new("Sensor", 7)
+        .endian(Endian::Big)
+        .field("flags", Scalar::U8)
+        .field("id", Scalar::U16)
+        .array("samples", Scalar::F64, 2)
+        .field("rssi", Scalar::I8)
+}
+
+fn sensor_values() -> Vec<Value> {
+    vec![
+        Value::U8(0x81),
+        Value::U16(0x1234),
+        Value::Array(vec![Value::F64(1.5), Value::F64(-0.25)]),
+        Value::I8(-60),
+    ]
+}
+
+#[test]
+fn cdata_layouts() {
+    let schema = CData::schema();
+    let layout = |tag| schema.layout(tag).unwrap();
+    assert_eq!((layout(0).size(), layout(0).offset(0), layout(0).offset(1), layout(0).offset(2)), (24, 8, 12, 16));
+    assert_eq!((layout(1).size(), layout(1).offset(0), layout(1).offset(1), layout(1).offset(2)), (64, 8, 12, 56));
+    assert_eq!((layout(2).size(), layout(2).offset(0), layout(2).offset(1)), (88, 4, 8));
+    assert_eq!(schema.max_size(), 88);
+    assert!(schema.layout(3).is_none());
+}
+
+#[test]
+fn alignment_and_padding() {
+    let layout = sensor();
+    assert_eq!(layout.align(), 8);
+    assert_eq!((0..4).map(|i| layout.offset(i)).collect::<Vec<_>>(), vec![8, 10, 16, 32]);
+    assert_eq!(layout.size(), 40);
+}
+
+#[test]
+fn big_endian_round_trip() {
+    let layout = sensor();
+    let bytes = layout.encode(&sensor_values()).unwrap();
+    assert_eq!(bytes.len(), 40);
+    assert_eq!(bytes[0], 7);
+    assert_eq!(&bytes[8..12], &[0x81, 0, 0x12, 0x34]);
+    assert_eq!(&bytes[16..24], &1.5f64.to_be_bytes());
+    assert_eq!(bytes[32], -60i8 as u8);
+    assert!(bytes[33..].iter().all(|b| *b == 0));
+    assert_eq!(layout.decode(&bytes, 0).unwrap(), sensor_values());
+}
+
+#[test]
+fn encode_checks_values() {
+    let layout = sensor();
+    assert_eq!(layout.encode(&sensor_values()[..3]), Err(SchemaError::FieldCount { expected: 4, found: 3 }));
+
+    let mut values = sensor_values();
+    values[1] = Value::U32(1);
+    assert_eq!(layout.encode(&values), Err(SchemaError::Mismatch { field: "id".to_string() }));
+
+    let mut values = sensor_values();
+    values[2] = Value::Array(vec![Value::F64(1.0)]);
+    assert_eq!(layout.encode(&values), Err(SchemaError::Mismatch { field: "samples".to_string() }));
+}
+
+#[test]
+fn decode_errors() {
+    let layout = sensor();
+    assert_eq!(layout.decode(&[7; 39], 5), Err(DecodeError::Truncated { offset: 5, needed: 40, available: 39 }));
+
+    let bad = Layout::new("Text", 1).array("text", Scalar::Char32, 2);
+    let mut bytes = bad.encode(&[Value::Array(vec![Value::Char('a'), Value::Char('b')])]).unwrap();
+    bytes[8..12].copy_from_slice(&0xd800u32.to_le_bytes());
+    assert_eq!(bad.decode(&bytes, 0), Err(DecodeError::InvalidChar { offset: 0, index: 1, code: 0xd800 }));
+}
+
+#[test]
+fn schema_extends_cdata() {
+    let mut layouts = CData::schema().layouts().to_vec();
+    layouts.push(sensor());
+    let schema = Schema::new(layouts.clone()).unwrap();
+
+    let sensor = Record { tag: 7, values: sensor_values() };
+    let mut bytes = CData::value(1, 2.0, 3).to_bytes();
+    bytes.extend(schema.encode(&sensor).unwrap());
+    bytes.extend(CData::message(4, ['x'; 20]).to_bytes());
+
+    let records = schema.decode(&bytes).unwrap();
+    assert_eq!(records.iter().map(|r| r.tag).collect::<Vec<_>>(), vec![0, 7, 2]);
+    assert_eq!(records[0].values, vec![Value::I32(1), Value::F32(2.0), Value::I64(3)]);
+    assert_eq!(records[1], sensor);
+
+    // Plain CData decoding does not know the new record
+    assert_eq!(CData::decode(&bytes), Err(DecodeError::UnknownTag { offset: 24, tag: 7 }));
+
+    layouts.push(Layout::new("Again", 7));
+    assert_eq!(Schema::new(layouts), Err(SchemaError::DuplicateTag(7)));
+    assert_eq!(schema.encode(&Record { tag: 9, values: vec![] }), Err(SchemaError::UnknownTag(9)));
+}